@@ -0,0 +1,51 @@
+//! Benchmarks rendering of URLs with default [`RenderOptions`].
+//!
+//! Run with `cargo bench --bench render`.
+
+use std::fmt::Write as _;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use mdlink::{RenderOptions, RenderedLink};
+use url::Url;
+
+/// Every URL in `golden.txt`, which covers each handler. Its expected renderings (every other
+/// non-comment line) are skipped.
+fn golden_urls() -> Vec<Url> {
+    include_str!("../tests/golden.txt")
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .step_by(2)
+        .map(|line| Url::parse(line).unwrap())
+        .collect()
+}
+
+/// GitHub issues and PRs, which most pasted links are.
+fn github_issue_urls() -> Vec<Url> {
+    (1..=100)
+        .map(|num| {
+            let kind = if num % 2 == 0 { "issues" } else { "pull" };
+            Url::parse(&format!("https://github.com/gfx-rs/wgpu/{kind}/{num}")).unwrap()
+        })
+        .collect()
+}
+
+fn render(c: &mut Criterion) {
+    let opts = RenderOptions::default();
+    let mut label = String::new();
+    for (name, urls) in [
+        ("golden corpus", golden_urls()),
+        ("GitHub issues and PRs", github_issue_urls()),
+    ] {
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                for url in &urls {
+                    label.clear();
+                    write!(label, "{}", RenderedLink::new(black_box(url), &opts)).unwrap();
+                }
+            })
+        });
+    }
+}
+
+criterion_group!(benches, render);
+criterion_main!(benches);
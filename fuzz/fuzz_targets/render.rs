@@ -0,0 +1,120 @@
+//! Renders arbitrary URLs with arbitrary options through each of the library's entry points,
+//! which must never panic or fail.
+//!
+//! Run with `cargo +nightly fuzz run render` from the repo root.
+
+#![no_main]
+
+use std::fmt::Write as _;
+
+use libfuzzer_sys::{
+    arbitrary::{self, Arbitrary},
+    fuzz_target,
+};
+use mdlink::{
+    is_recognized_with, normalize, parse_scp_like_remote, trim_tracking_params, try_write_label,
+    write_html_anchor, CommitSubjectLookup, FileUrlDisplay, IssueTitleLookup, RenderOptions,
+    RenderedLink, RepoPrefix,
+};
+use url::Url;
+
+#[derive(Arbitrary, Debug)]
+struct Input<'a> {
+    url: &'a str,
+    opts: Options<'a>,
+}
+
+/// Fuzzed [`RenderOptions`]. Lookups return the same title or subject for everything.
+#[derive(Arbitrary, Debug)]
+struct Options<'a> {
+    disabled_hosts: Vec<&'a str>,
+    github_hosts: Vec<&'a str>,
+    use_backticks: bool,
+    path_separator: &'a str,
+    hide_default_branch: Option<&'a str>,
+    issue_title: Option<&'a str>,
+    commit_subject: Option<&'a str>,
+    link_table: Vec<(&'a str, &'a str)>,
+    annotate_host: bool,
+    strip_query_on_match: bool,
+    image_hosts: Vec<&'a str>,
+    mark_tag_refs: bool,
+    home_org: Option<&'a str>,
+    repo_only: bool,
+    full_file_paths: bool,
+    issue_format: Option<&'a str>,
+    aliases: Vec<(&'a str, &'a str)>,
+    mdn_locale: Option<&'a str>,
+}
+
+impl Options<'_> {
+    fn to_render_options(&self) -> RenderOptions {
+        let owned = |strs: &[&str]| strs.iter().map(|s| s.to_string()).collect();
+        let owned_pairs = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        RenderOptions {
+            disabled_hosts: owned(&self.disabled_hosts),
+            github_hosts: owned(&self.github_hosts),
+            use_backticks: self.use_backticks,
+            path_separator: self.path_separator.to_owned().into(),
+            hide_default_branch: self.hide_default_branch.map(str::to_owned),
+            issue_titles: self.issue_title.map(|title| {
+                let title = title.to_owned();
+                IssueTitleLookup(Box::new(move |_issue| Some(title.clone())))
+            }),
+            commit_subjects: self.commit_subject.map(|subject| {
+                let subject = subject.to_owned();
+                CommitSubjectLookup(Box::new(move |_repo, _sha| Some(subject.clone())))
+            }),
+            link_table: owned_pairs(&self.link_table),
+            annotate_host: self.annotate_host,
+            strip_query_on_match: self.strip_query_on_match,
+            image_hosts: owned(&self.image_hosts),
+            mark_tag_refs: self.mark_tag_refs,
+            home_org: self.home_org.map(str::to_owned),
+            repo_prefix: if self.repo_only {
+                RepoPrefix::RepoOnly
+            } else {
+                RepoPrefix::Full
+            },
+            file_url_display: if self.full_file_paths {
+                FileUrlDisplay::FullPath
+            } else {
+                FileUrlDisplay::Basename
+            },
+            issue_format: self
+                .issue_format
+                .map_or(RenderOptions::default().issue_format, |format| {
+                    format.to_owned().into()
+                }),
+            aliases: owned_pairs(&self.aliases),
+            mdn_locale: self.mdn_locale.map(str::to_owned),
+        }
+    }
+}
+
+fuzz_target!(|input: Input<'_>| {
+    let _ = parse_scp_like_remote(input.url);
+    let Ok(mut url) = Url::parse(input.url) else {
+        return;
+    };
+    let opts = input.opts.to_render_options();
+    let mut rendered = String::new();
+    let mut render_all = |url: &Url| {
+        write!(rendered, "{}", RenderedLink::new(url, &opts))
+            .unwrap_or_else(|e| panic!("failed to render {url}: {e}"));
+        try_write_label(url, &opts, &mut rendered)
+            .unwrap_or_else(|e| panic!("failed to render a label for {url}: {e}"));
+        write_html_anchor(url, &opts, &mut rendered)
+            .unwrap_or_else(|e| panic!("failed to render an anchor for {url}: {e}"));
+        let _ = is_recognized_with(url, &opts);
+    };
+    render_all(&url);
+    trim_tracking_params(&mut url);
+    normalize(&mut url);
+    render_all(&url);
+});
@@ -0,0 +1,55 @@
+use std::{fmt, str::Split, sync::OnceLock};
+
+use itertools::Itertools;
+use url::Url;
+
+use crate::{handlers::UrlHandler, FancyMarkdownMatched, RenderOptions};
+
+pub(crate) struct Arxiv;
+
+impl UrlHandler for Arxiv {
+    fn hosts(&self) -> &'static str {
+        "arxiv.org"
+    }
+
+    fn description(&self) -> &'static str {
+        "papers"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "arxiv.org"
+    }
+
+    fn render<'a>(
+        &self,
+        _url: &'a Url,
+        path_segments: Split<'a, char>,
+        _opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some((kind @ ("abs" | "pdf"), id)) = path_segments.collect_tuple() {
+            let id = match kind {
+                "pdf" => id.strip_suffix(".pdf").unwrap_or(id),
+                _ => id,
+            };
+            static ARXIV_ID_RE: OnceLock<regex::Regex> = OnceLock::new();
+            let arxiv_id_re = ARXIV_ID_RE.get_or_init(|| {
+                regex::Regex::new(concat!(
+                    r"^\d{4}\.\d{4,5}", // `YYMM.NNNNN`
+                    r"(?:v\d+)?$",
+                ))
+                .unwrap()
+            });
+            if arxiv_id_re.is_match(id) {
+                let qualifier = match kind {
+                    "pdf" => " (PDF)",
+                    _ => "",
+                };
+                write!(f, "arXiv:{id}{qualifier}")?;
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
@@ -0,0 +1,46 @@
+use std::{fmt, str::Split};
+
+use itertools::Itertools;
+use url::Url;
+
+use crate::{decode, handlers::UrlHandler, FancyMarkdownMatched, RenderOptions};
+
+pub(crate) struct Confluence;
+
+impl UrlHandler for Confluence {
+    fn hosts(&self) -> &'static str {
+        "*.atlassian.net"
+    }
+
+    fn description(&self) -> &'static str {
+        "Confluence pages"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host.ends_with(".atlassian.net")
+    }
+
+    fn render<'a>(
+        &self,
+        _url: &'a Url,
+        path_segments: Split<'a, char>,
+        _opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some(("wiki", "spaces", _space, "pages", page_id, title)) =
+            path_segments.collect_tuple()
+        {
+            if !page_id.is_empty() && page_id.chars().all(|c| c.is_ascii_digit()) {
+                // Titles are encoded like form values, i.e., spaces may be either `+` or `%20`.
+                let title = title.replace('+', " ");
+                let title = decode(&title);
+                if !title.is_empty() {
+                    write!(f, "Confluence: {title}")?;
+                    return Ok(FancyMarkdownMatched::Yes);
+                }
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
@@ -0,0 +1,75 @@
+use std::{fmt, str::Split};
+
+use url::Url;
+
+use crate::{alias, code, escape, handlers::UrlHandler, FancyMarkdownMatched, RenderOptions};
+
+pub(crate) struct AzureDevOps;
+
+impl UrlHandler for AzureDevOps {
+    fn hosts(&self) -> &'static str {
+        "dev.azure.com"
+    }
+
+    fn description(&self) -> &'static str {
+        "Git repos, files, and PRs"
+    }
+
+    fn uses_query(&self, url: &Url) -> bool {
+        // Files are selected with a `path` query parameter; other parameters (e.g., `version`)
+        // aren't reflected in labels.
+        url.query_pairs().any(|(k, _v)| k == "path")
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "dev.azure.com"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        let (Some(_org), Some(_project), Some("_git"), Some(repo)) = (
+            path_segments.next(),
+            path_segments.next(),
+            path_segments.next(),
+            path_segments.next(),
+        ) else {
+            return Ok(FancyMarkdownMatched::No);
+        };
+        if repo.is_empty() {
+            return Ok(FancyMarkdownMatched::No);
+        }
+        let repo = code(opts, alias(opts, repo));
+        match (path_segments.next(), path_segments.next()) {
+            (Some("pullrequest"), Some(id))
+                if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                write!(f, "{repo}#{id}")?;
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+            (None | Some(""), None) => {
+                // Files are selected with a query parameter, rather than the path.
+                let file_path = url
+                    .query_pairs()
+                    .find_map(|(k, v)| (k == "path").then_some(v))
+                    .filter(|path| !path.trim_start_matches('/').is_empty());
+                match file_path {
+                    Some(file_path) => {
+                        let sep = &opts.path_separator;
+                        let file_path = code(opts, escape(file_path.trim_start_matches('/')));
+                        write!(f, "{repo}{sep}{file_path}")?;
+                    }
+                    None => write!(f, "{repo}")?,
+                }
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+            _ => (),
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
@@ -0,0 +1,85 @@
+use std::{fmt, str::Split};
+
+use url::Url;
+
+use crate::{decode, handlers::UrlHandler, FancyMarkdownMatched, RenderOptions};
+
+pub(crate) struct Dropbox;
+
+impl UrlHandler for Dropbox {
+    fn hosts(&self) -> &'static str {
+        "dropbox.com"
+    }
+
+    fn description(&self) -> &'static str {
+        "shared files"
+    }
+
+    fn uses_query(&self, url: &Url) -> bool {
+        // `scl/fi` links can't be opened without their key.
+        url.query_pairs().any(|(k, _v)| k == "rlkey")
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        matches!(host, "www.dropbox.com" | "dropbox.com")
+    }
+
+    fn render<'a>(
+        &self,
+        _url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        _opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        // Shared files look like `s/<id>/<file>` or, more recently, `scl/fi/<id>/<file>`.
+        let file = match (path_segments.next(), path_segments.next()) {
+            (Some("s"), Some(_id)) => path_segments.next(),
+            (Some("scl"), Some("fi")) => path_segments.nth(1),
+            _ => None,
+        };
+        if let Some(file) = file.filter(|file| !file.is_empty()) {
+            let file = decode(file);
+            write!(f, "Dropbox: {file}")?;
+            return Ok(FancyMarkdownMatched::Yes);
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct GoogleDrive;
+
+impl UrlHandler for GoogleDrive {
+    fn hosts(&self) -> &'static str {
+        "drive.google.com"
+    }
+
+    fn description(&self) -> &'static str {
+        "files and folders"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "drive.google.com"
+    }
+
+    fn render<'a>(
+        &self,
+        _url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        _opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        // Names aren't part of Drive URLs, so only the kind of item can be shown.
+        let kind = match (path_segments.next(), path_segments.next()) {
+            (Some("file"), Some("d")) => "file",
+            (Some("drive"), Some("folders")) => "folder",
+            _ => return Ok(FancyMarkdownMatched::No),
+        };
+        if path_segments.next().is_some_and(|id| !id.is_empty()) {
+            write!(f, "Google Drive {kind}")?;
+            return Ok(FancyMarkdownMatched::Yes);
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
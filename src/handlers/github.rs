@@ -0,0 +1,758 @@
+use std::{
+    fmt::{self, Display},
+    str::Split,
+    sync::OnceLock,
+};
+
+use itertools::Itertools;
+use joinery::JoinableIterator;
+use lazy_format::make_lazy_format;
+use url::Url;
+
+use crate::{
+    code, decode, decode_prose, escape_prose, handlers::UrlHandler, FancyMarkdownMatched,
+    RenderOptions, RepoPrefix,
+};
+
+pub(crate) struct GitHub;
+
+impl UrlHandler for GitHub {
+    fn hosts(&self) -> &'static str {
+        "github.com"
+    }
+
+    fn description(&self) -> &'static str {
+        "repos, issues, PRs, files, commits, releases, and more"
+    }
+
+    fn uses_query(&self, url: &Url) -> bool {
+        // These are the pages whose labels are read from the query in `render`.
+        let has_param = |name| url.query_pairs().any(|(k, _v)| k == name);
+        let Some(path_segments) = url.path_segments() else {
+            return false;
+        };
+        match path_segments.collect::<Vec<_>>().as_slice() {
+            ["search"] => true,
+            // Profiles and repo home pages
+            [_] | [_, ""] | [_, _] => has_param("tab"),
+            [_, _, "issues" | "discussions"] | [_, _, "issues" | "discussions", ""] => {
+                has_param("q")
+            }
+            [_, _, "blob", ..] => has_param("plain"),
+            _ => false,
+        }
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "github.com"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some(("search",)) = path_segments.clone().collect_tuple() {
+            let query_param = |name| {
+                url.query_pairs()
+                    .find_map(|(k, v)| (k == name).then_some(v))
+            };
+            if let Some(query) = query_param("q").filter(|q| !q.is_empty()) {
+                let scope = match query_param("type").as_deref() {
+                    Some("code") => "code ",
+                    Some("repositories") => "repository ",
+                    Some("issues") => "issue ",
+                    Some("pullrequests") => "pull request ",
+                    Some("discussions") => "discussion ",
+                    Some("commits") => "commit ",
+                    Some("users") => "user ",
+                    _ => "",
+                };
+                write!(f, "GitHub {scope}search: {}", escape_prose(&query))?;
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+        let mut sponsors_segments = path_segments.clone();
+        if let (Some("sponsors"), Some(user), Some("") | None, None) = (
+            sponsors_segments.next(),
+            sponsors_segments.next(),
+            sponsors_segments.next(),
+            sponsors_segments.next(),
+        ) {
+            if !user.is_empty() {
+                write!(f, "Sponsor @{}", decode_prose(user))?;
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+        let mut profile_segments = path_segments.clone();
+        if let (Some(user), Some("") | None, None) = (
+            profile_segments.next(),
+            profile_segments.next(),
+            profile_segments.next(),
+        ) {
+            let is_user_or_org = !user.is_empty()
+                && !matches!(
+                    user,
+                    "about"
+                        | "apps"
+                        | "codespaces"
+                        | "collections"
+                        | "copilot"
+                        | "dashboard"
+                        | "enterprise"
+                        | "explore"
+                        | "features"
+                        | "login"
+                        | "marketplace"
+                        | "new"
+                        | "notifications"
+                        | "organizations"
+                        | "orgs"
+                        | "pricing"
+                        | "pulls"
+                        | "search"
+                        | "issues"
+                        | "settings"
+                        | "signup"
+                        | "sponsors"
+                        | "stars"
+                        | "topics"
+                        | "trending"
+                        | "users"
+                );
+            if is_user_or_org {
+                write!(f, "@{}", decode_prose(user))?;
+                let tab = url
+                    .query_pairs()
+                    .find_map(|(k, v)| (k == "tab").then_some(v));
+                if let Some(
+                    tab @ ("repositories" | "projects" | "packages" | "stars" | "followers"
+                    | "following" | "sponsoring"),
+                ) = tab.as_deref()
+                {
+                    write!(f, " {tab}")?;
+                }
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+        if let Some(("orgs" | "users", org, "packages", _registry, "package", package)) =
+            path_segments.clone().next_tuple()
+        {
+            if !package.is_empty() {
+                write!(f, "{} package", code(opts, format_args!("{org}/{package}")))?;
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+        if let Some((org, repo)) = path_segments
+            .next_tuple()
+            .filter(|(org, repo)| !org.is_empty() && !repo.is_empty())
+        {
+            let alias = opts.aliases.get(&format!("{org}/{repo}"));
+            // Repos in the home org are unambiguous without it, and other orgs may be left out too.
+            let is_repo_only =
+                opts.home_org.as_deref() == Some(org) || opts.repo_prefix == RepoPrefix::RepoOnly;
+            let org_repo = code(
+                opts,
+                make_lazy_format!(|f| match alias {
+                    Some(alias) => write!(f, "{alias}"),
+                    None if is_repo_only => write!(f, "{repo}"),
+                    None => write!(f, "{org}/{repo}"),
+                }),
+            );
+            let sep = &opts.path_separator;
+            if path_segments.clone().next().is_none() {
+                let tab = url
+                    .query_pairs()
+                    .find_map(|(k, v)| (k == "tab").then_some(v));
+                if tab.as_deref() == Some("readme-ov-file") {
+                    write!(f, "{org_repo} README")?;
+                    return Ok(FancyMarkdownMatched::Yes);
+                }
+                write!(f, "{org_repo}")?;
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+            let mut listing_segments = path_segments.clone();
+            if let (Some(listing @ ("issues" | "discussions")), Some("") | None, None) = (
+                listing_segments.next(),
+                listing_segments.next(),
+                listing_segments.next(),
+            ) {
+                let is_filtered = url.query_pairs().any(|(k, v)| k == "q" && !v.is_empty());
+                if is_filtered {
+                    write!(f, "{org_repo} {listing} (filtered)")?;
+                } else {
+                    write!(f, "{org_repo} {listing}")?;
+                }
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+            let mut issue_segments = path_segments.clone();
+            if let Some((kind @ ("issues" | "pull"), issue_num)) = issue_segments.next_tuple() {
+                if kind == "pull" {
+                    let diff = issue_num
+                        .rsplit_once('.')
+                        .filter(|(num, _kind)| {
+                            !num.is_empty() && num.chars().all(|c| c.is_ascii_digit())
+                        })
+                        .filter(|_| issue_segments.clone().next().is_none());
+                    if let Some((issue_num, diff_kind @ ("diff" | "patch"))) = diff {
+                        write!(f, "{org_repo}#{issue_num} ({diff_kind})")?;
+                        return Ok(FancyMarkdownMatched::Yes);
+                    }
+                    match issue_segments.next() {
+                        Some("commits") => {
+                            if let Some(commit) =
+                                issue_segments.next().filter(|commit| !commit.is_empty())
+                            {
+                                let commit = commit.get(..7).unwrap_or(commit);
+                                write!(f, "{org_repo}#{issue_num} @{}", code(opts, commit))?;
+                                return Ok(FancyMarkdownMatched::Yes);
+                            }
+                        }
+                        Some("files") => {
+                            let range = issue_segments
+                                .next()
+                                .and_then(|range| range.split_once(".."))
+                                .filter(|(start, end)| !start.is_empty() && !end.is_empty());
+                            if let Some((start, end)) = range {
+                                let abbreviate = |sha: &'a str| sha.get(..7).unwrap_or(sha);
+                                write!(
+                                    f,
+                                    "{org_repo}#{issue_num} (diff {}..{})",
+                                    abbreviate(start),
+                                    abbreviate(end)
+                                )?;
+                                return Ok(FancyMarkdownMatched::Yes);
+                            }
+                            let is_review_comment = url
+                                .fragment()
+                                .and_then(|frag| frag.strip_prefix('r'))
+                                .is_some_and(|id| {
+                                    !id.is_empty() && id.chars().all(|c| c.is_ascii_digit())
+                                });
+                            if is_review_comment {
+                                write!(f, "{org_repo}#{issue_num} (review comment)")?;
+                                return Ok(FancyMarkdownMatched::Yes);
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+                // Some repos number PRs in a well-known series, which reads better than the repo.
+                let pr_series = match (kind, org, repo) {
+                    ("pull", "rust-lang", "rfcs") => Some("RFC"),
+                    _ => None,
+                };
+                match pr_series {
+                    Some(series) => write!(f, "{series} #{issue_num}")?,
+                    None => write_issue_label(f, &opts.issue_format, &org_repo, issue_num, url)?,
+                }
+                let title = opts
+                    .issue_titles
+                    .as_ref()
+                    .and_then(|lookup| (lookup.0)(&format!("{org}/{repo}#{issue_num}")));
+                if let Some(title) = title {
+                    write!(f, ": {}", escape_prose(&title))?;
+                }
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+
+            {
+                let mut path_segments = path_segments.clone();
+                match path_segments.next_tuple() {
+                    Some(("blob", commitish)) => {
+                        enum LineNumberSpec<'a> {
+                            Single(&'a str),
+                            Range { start: &'a str, end: &'a str },
+                        }
+                        let commitish = decode(commitish);
+                        let file_path_segments = path_segments.map(decode);
+                        let line_num_spec = url.fragment().and_then(|frag| {
+                            static LINE_NUM_SPEC_RE: OnceLock<regex::Regex> = OnceLock::new();
+                            let line_num_spec_re = LINE_NUM_SPEC_RE.get_or_init(|| {
+                                regex::Regex::new(concat!(
+                                    r#"L(?P<start>\d+)"#,
+                                    r#"(:?-L(?P<end>\d+))?"#,
+                                ))
+                                .unwrap()
+                            });
+                            line_num_spec_re.captures(frag).map(|caps| {
+                                let start = caps.name("start").map(|m| m.as_str()).expect(concat!(
+                                    "matched line number spec. regex, ",
+                                    "but unconditional `start` capture not found"
+                                ));
+
+                                caps.name("end")
+                                    .map(|m| m.as_str())
+                                    .map(|end| LineNumberSpec::Range { start, end })
+                                    .unwrap_or(LineNumberSpec::Single(start))
+                            })
+                        });
+                        write!(
+                            f,
+                            "{org_repo}{}{sep}{}{}",
+                            repo_commitish(opts, &commitish),
+                            code(opts, file_path_segments.join_with('/')),
+                            make_lazy_format!(|f| {
+                                match line_num_spec {
+                                    Some(LineNumberSpec::Single(num)) => {
+                                        write!(f, ":{num}")
+                                    }
+                                    Some(LineNumberSpec::Range { start, end }) => {
+                                        write!(f, ":{start}-{end}")
+                                    }
+                                    None => Ok(()),
+                                }
+                            })
+                        )?;
+                        // Forces the source of rendered files (e.g., Markdown) to be shown.
+                        if url.query_pairs().any(|(k, v)| k == "plain" && v == "1") {
+                            write!(f, " (source view)")?;
+                        }
+                        return Ok(FancyMarkdownMatched::Yes);
+                    }
+                    Some(("tree", commitish)) if !commitish.is_empty() => {
+                        let commitish = decode(commitish);
+                        let dir_path_segments = path_segments
+                            .filter(|segment| !segment.is_empty())
+                            .map(decode);
+                        write!(f, "{org_repo}{}", repo_commitish(opts, &commitish))?;
+                        if dir_path_segments.clone().next().is_some() {
+                            write!(f, "{sep}{}", code(opts, dir_path_segments.join_with('/')))?;
+                        }
+                        return Ok(FancyMarkdownMatched::Yes);
+                    }
+                    Some(("commit", commitish)) => {
+                        if path_segments.clone().next().is_none() {
+                            if let Some((commitish, diff_kind @ ("diff" | "patch"))) =
+                                commitish.rsplit_once('.')
+                            {
+                                write!(
+                                    f,
+                                    "{org_repo}{sep}{} ({diff_kind})",
+                                    code(opts, decode(commitish))
+                                )?;
+                                return Ok(FancyMarkdownMatched::Yes);
+                            }
+                            write!(f, "{org_repo}{sep}{}", code(opts, decode(commitish)))?;
+                            let subject = opts
+                                .commit_subjects
+                                .as_ref()
+                                .and_then(|lookup| (lookup.0)(&format!("{org}/{repo}"), commitish));
+                            if let Some(subject) = subject {
+                                write!(f, " (\"{}\")", escape_prose(&subject))?;
+                            }
+                            return Ok(FancyMarkdownMatched::Yes);
+                        }
+
+                        let file_path_segments = path_segments.map(decode);
+                        write!(
+                            f,
+                            "{org_repo}{sep}{}{sep}{}",
+                            code(opts, decode(commitish)),
+                            code(opts, file_path_segments.join_with('/')),
+                        )?;
+                        return Ok(FancyMarkdownMatched::Yes);
+                    }
+                    Some(("commits", commitish)) if !commitish.is_empty() => {
+                        let commitish = decode(commitish);
+                        if let None | Some("") = path_segments.clone().next() {
+                            write!(f, "{org_repo}{} history", repo_commitish(opts, &commitish))?;
+                            return Ok(FancyMarkdownMatched::Yes);
+                        }
+
+                        let file_path_segments = path_segments.map(decode);
+                        write!(
+                            f,
+                            "{org_repo}{}{sep}{} history",
+                            repo_commitish(opts, &commitish),
+                            code(opts, file_path_segments.join_with('/')),
+                        )?;
+                        return Ok(FancyMarkdownMatched::Yes);
+                    }
+                    Some(("releases", "tag")) => {
+                        if let Some(tag) = path_segments.next() {
+                            let tag = decode(tag);
+                            match (path_segments.next(), path_segments.next()) {
+                                (Some(""), None) | (None, ..) => {
+                                    static COMPONENT_VERSION_RE: OnceLock<regex::Regex> =
+                                        OnceLock::new();
+
+                                    if let Some(captures) = COMPONENT_VERSION_RE
+                                        .get_or_init(|| {
+                                            regex::Regex::new(concat!(
+                                                r"(?P<component>.+)",
+                                                "-",
+                                                r"(?P<version>v\d+(:?\.\d+){0,2})"
+                                            ))
+                                            .unwrap()
+                                        })
+                                        .captures(&tag)
+                                    {
+                                        let component = &captures["component"];
+                                        let version = &captures["version"];
+                                        write!(f, "{} {version}", code(opts, component))?;
+                                    } else {
+                                        write!(f, "{} tag release", code(opts, tag))?;
+                                    }
+                                    return Ok(FancyMarkdownMatched::Yes);
+                                }
+                                _ => (),
+                            }
+                        }
+                    }
+                    Some(("releases", "download")) => {
+                        let release_asset = path_segments
+                            .collect_tuple()
+                            .filter(|(tag, asset)| !tag.is_empty() && !asset.is_empty());
+                        if let Some((tag, asset)) = release_asset {
+                            write!(f, "{org_repo} {}: {}", decode(tag), decode(asset))?;
+                            return Ok(FancyMarkdownMatched::Yes);
+                        }
+                    }
+                    Some(("compare", _)) => {
+                        // Refs may contain `/`s, so the comparison spans the rest of the path.
+                        let spec = url
+                            .path()
+                            .splitn(5, '/')
+                            .nth(4)
+                            .map(|spec| spec.trim_end_matches('/'))
+                            .unwrap_or_default();
+                        // Either side may be qualified with a fork's owner (`user:ref`). The
+                        // three-dot separator must be tried first, since it contains the two-dot
+                        // one.
+                        let sides = spec.split_once("...").or_else(|| spec.split_once(".."));
+                        let is_valid = match sides {
+                            Some((base, head)) => !base.is_empty() && !head.is_empty(),
+                            None => !spec.is_empty(),
+                        };
+                        if is_valid {
+                            write!(f, "{org_repo}: {}", decode(spec))?;
+                            return Ok(FancyMarkdownMatched::Yes);
+                        }
+                    }
+                    Some(("pkgs", _registry)) => {
+                        if let Some(package) = path_segments.next().filter(|p| !p.is_empty()) {
+                            write!(f, "{} package", code(opts, format_args!("{org}/{package}")))?;
+                            return Ok(FancyMarkdownMatched::Yes);
+                        }
+                    }
+                    Some(("discussions", "categories")) => {
+                        if let (Some(category), Some("") | None) =
+                            (path_segments.next(), path_segments.next())
+                        {
+                            if !category.is_empty() {
+                                write!(f, "{org_repo} discussions: {}", decode(category))?;
+                                return Ok(FancyMarkdownMatched::Yes);
+                            }
+                        }
+                    }
+                    Some(("labels", label)) if !label.is_empty() => {
+                        if let None | Some("") = path_segments.next() {
+                            write!(f, "{org_repo} label: {}", decode(label))?;
+                            return Ok(FancyMarkdownMatched::Yes);
+                        }
+                    }
+                    Some(("actions", "workflows")) => {
+                        if let Some((workflow_file,)) = path_segments.collect_tuple() {
+                            if !workflow_file.is_empty() {
+                                write!(f, "{org_repo} CI workflow: {}", decode(workflow_file))?;
+                                return Ok(FancyMarkdownMatched::Yes);
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+            }
+
+            // Non-content areas of a repo are labeled by area, regardless of the page within.
+            if let Some(area @ ("settings" | "insights" | "pulse" | "network" | "graphs")) =
+                path_segments.next()
+            {
+                write!(f, "{org_repo} {area}")?;
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+/// Renders a commit-ish from a URL path. `HEAD` is a symbolic ref rather than a name or SHA, so
+/// it's written as plain text.
+fn commitish_ref<'a>(opts: &'a RenderOptions, commitish: &'a str) -> impl Display + 'a {
+    make_lazy_format!(|f| if commitish == "HEAD" {
+        write!(f, "HEAD")
+    } else {
+        write!(f, "{}", code(opts, commitish))
+    })
+}
+
+/// Renders a commit-ish that follows a repo, along with the separator before it. Version-like
+/// tags are written as `@tag` instead with [`RenderOptions::mark_tag_refs`], and
+/// [`RenderOptions::hide_default_branch`] isn't written at all.
+fn repo_commitish<'a>(opts: &'a RenderOptions, commitish: &'a str) -> impl Display + 'a {
+    let is_hidden_default_branch = opts.hide_default_branch.as_deref() == Some(commitish);
+    static TAG_RE: OnceLock<regex::Regex> = OnceLock::new();
+    let is_tag = opts.mark_tag_refs
+        && TAG_RE
+            .get_or_init(|| regex::Regex::new(r"^v?\d+\.\d+(?:\.\d+)?(?:[-+][\w.-]+)?$").unwrap())
+            .is_match(commitish);
+    make_lazy_format!(|f| if is_hidden_default_branch {
+        Ok(())
+    } else if is_tag {
+        write!(f, "@{}", code(opts, commitish))
+    } else {
+        write!(
+            f,
+            "{}{}",
+            opts.path_separator,
+            commitish_ref(opts, commitish)
+        )
+    })
+}
+
+/// Writes the label of an issue or PR from [`RenderOptions::issue_format`]. Anything other than
+/// its fields, including unknown `{field}`s, is written as-is.
+fn write_issue_label(
+    f: &mut dyn fmt::Write,
+    template: &str,
+    prefix: impl Display,
+    num: &str,
+    url: &Url,
+) -> fmt::Result {
+    let mut rest = template;
+    while let Some((literal, after_brace)) = rest.split_once('{') {
+        f.write_str(literal)?;
+        let field = after_brace.split_once('}').and_then(|(name, after)| {
+            let value: &dyn Display = match name {
+                "prefix" => &prefix,
+                "num" => &num,
+                "url" => url,
+                _ => return None,
+            };
+            Some((value, after))
+        });
+        rest = match field {
+            Some((value, after)) => {
+                write!(f, "{value}")?;
+                after
+            }
+            None => {
+                f.write_char('{')?;
+                after_brace
+            }
+        };
+    }
+    f.write_str(rest)
+}
+
+/// REST API endpoints for things that have a web page, which are labeled (and linked) like the
+/// page.
+pub(crate) struct GitHubApi;
+
+impl GitHubApi {
+    fn web_url(url: &Url) -> Option<Url> {
+        let mut path_segments = url.path_segments()?;
+        let (org, repo) = match path_segments.next_tuple()? {
+            ("repos", org, repo) if !org.is_empty() && !repo.is_empty() => (org, repo),
+            _ => return None,
+        };
+        let rest = match (
+            path_segments.next(),
+            path_segments.next(),
+            path_segments.next(),
+        ) {
+            (None | Some(""), None, None) => String::new(),
+            (Some(kind @ ("issues" | "pulls" | "commits")), Some(id), None) if !id.is_empty() => {
+                let kind = match kind {
+                    "issues" => "issues",
+                    "pulls" => "pull",
+                    _ => "commit",
+                };
+                format!("/{kind}/{id}")
+            }
+            _ => return None,
+        };
+        format!("https://github.com/{org}/{repo}{rest}")
+            .parse()
+            .ok()
+    }
+}
+
+impl UrlHandler for GitHubApi {
+    fn hosts(&self) -> &'static str {
+        "api.github.com"
+    }
+
+    fn description(&self) -> &'static str {
+        "repo, issue, PR, and commit endpoints"
+    }
+
+    fn link_target(&self, url: &Url, _opts: &RenderOptions) -> Option<Url> {
+        Self::web_url(url)
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "api.github.com"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        _path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        // Labels and links are for `github.com`, so they shouldn't be made if it's disabled.
+        if opts.is_disabled_host("github.com") {
+            return Ok(FancyMarkdownMatched::No);
+        }
+        if let Some(web_url) = Self::web_url(url) {
+            if let Some(path_segments) = web_url.path_segments() {
+                return GitHub.render(&web_url, path_segments, opts, f);
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct RawGist;
+
+impl UrlHandler for RawGist {
+    fn hosts(&self) -> &'static str {
+        "gist.githubusercontent.com"
+    }
+
+    fn description(&self) -> &'static str {
+        "raw gist files"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "gist.githubusercontent.com"
+    }
+
+    fn render<'a>(
+        &self,
+        _url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some((user, _gist_id, "raw")) = path_segments.next_tuple() {
+            let file = match (path_segments.next(), path_segments.next()) {
+                (Some(file), None) | (Some(_), Some(file)) => Some(file),
+                (None, _) => None,
+            };
+            if let Some(file) = file.filter(|file| !file.is_empty()) {
+                if path_segments.next().is_none() {
+                    write!(
+                        f,
+                        "gist by {}: {}",
+                        code(opts, user),
+                        code(opts, decode(file))
+                    )?;
+                    return Ok(FancyMarkdownMatched::Yes);
+                }
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct Gist;
+
+impl UrlHandler for Gist {
+    fn hosts(&self) -> &'static str {
+        "gist.github.com"
+    }
+
+    fn description(&self) -> &'static str {
+        "gists and their revisions"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "gist.github.com"
+    }
+
+    fn render<'a>(
+        &self,
+        _url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some((user, gist_id)) = path_segments.next_tuple() {
+            if user.is_empty() || gist_id.is_empty() {
+                return Ok(FancyMarkdownMatched::No);
+            }
+            let user = code(opts, user);
+            match (path_segments.next(), path_segments.next()) {
+                (None | Some(""), None) => write!(f, "gist by {user}")?,
+                (Some("revisions"), None | Some("")) => write!(f, "gist by {user} (revisions)")?,
+                (Some(revision), None | Some(""))
+                    if revision.chars().all(|c| c.is_ascii_hexdigit()) =>
+                {
+                    write!(f, "gist by {user} @{}", code(opts, revision))?
+                }
+                _ => return Ok(FancyMarkdownMatched::No),
+            }
+            return Ok(FancyMarkdownMatched::Yes);
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+/// A generic fallback for GitHub Pages project sites, which should be tried after more specific
+/// handlers for `*.github.io` hosts have declined.
+pub(crate) struct GitHubPages;
+
+impl UrlHandler for GitHubPages {
+    fn hosts(&self) -> &'static str {
+        "*.github.io"
+    }
+
+    fn description(&self) -> &'static str {
+        "GitHub Pages project sites"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host.ends_with(".github.io")
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        let org = url
+            .host_str()
+            .and_then(|host| host.strip_suffix(".github.io"));
+        // Project sites are served under their repo's name, as a directory. A lone last segment
+        // is a page of the user or org site instead, e.g., `octocat.github.io/about.html`.
+        let repo = path_segments
+            .next()
+            .filter(|repo| !repo.is_empty() && path_segments.clone().next().is_some());
+        if let Some((org, repo)) = org.zip(repo) {
+            let org_repo = format!("{org}/{}", decode(repo));
+            let org_repo = code(opts, &org_repo);
+            let page = path_segments
+                .rfind(|segment| !segment.is_empty())
+                .map(|page| page.strip_suffix(".html").unwrap_or(page))
+                .filter(|page| *page != "index");
+            match page {
+                Some(page) => write!(f, "{org_repo} docs: {}", decode_prose(page))?,
+                None => write!(f, "{org_repo} docs")?,
+            }
+            return Ok(FancyMarkdownMatched::Yes);
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
@@ -0,0 +1,85 @@
+use std::{fmt, str::Split, sync::OnceLock};
+
+use joinery::JoinableIterator;
+use url::Url;
+
+use crate::{alias, code, decode, handlers::UrlHandler, FancyMarkdownMatched, RenderOptions};
+
+pub(crate) struct GitLab;
+
+impl UrlHandler for GitLab {
+    fn hosts(&self) -> &'static str {
+        "gitlab.com"
+    }
+
+    fn description(&self) -> &'static str {
+        "CI pipelines and jobs, and files"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "gitlab.com"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        // Projects may be nested in subgroups, so everything before the `-` separator is the
+        // project path.
+        let project_path = path_segments
+            .by_ref()
+            .take_while(|segment| *segment != "-")
+            .collect::<Vec<_>>();
+        if project_path.len() < 2 || project_path.iter().any(|segment| segment.is_empty()) {
+            return Ok(FancyMarkdownMatched::No);
+        }
+        let project_path = project_path.iter().join_with('/').to_string();
+        let project = code(opts, alias(opts, &project_path));
+
+        let sep = &opts.path_separator;
+        match (path_segments.next(), path_segments.next()) {
+            (Some(kind @ ("pipelines" | "jobs")), Some(id))
+                if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                if let None | Some("") = path_segments.next() {
+                    let kind = match kind {
+                        "pipelines" => "pipeline",
+                        _ => "job",
+                    };
+                    write!(f, "{project} {kind} {id}")?;
+                    return Ok(FancyMarkdownMatched::Yes);
+                }
+            }
+            (Some("blob"), Some(commitish)) if !commitish.is_empty() => {
+                // Unlike GitHub, GitLab writes line ranges as `L10-20`.
+                let line_num_spec = url.fragment().and_then(|frag| {
+                    static LINE_NUM_SPEC_RE: OnceLock<regex::Regex> = OnceLock::new();
+                    let line_num_spec_re = LINE_NUM_SPEC_RE.get_or_init(|| {
+                        regex::Regex::new(concat!(r"^L(?P<start>\d+)", r"(?:-(?P<end>\d+))?$"))
+                            .unwrap()
+                    });
+                    line_num_spec_re.captures(frag)
+                });
+                write!(
+                    f,
+                    "{project}{sep}{}{sep}{}",
+                    code(opts, decode(commitish)),
+                    code(opts, path_segments.map(decode).join_with('/')),
+                )?;
+                if let Some(caps) = line_num_spec {
+                    write!(f, ":{}", &caps["start"])?;
+                    if let Some(end) = caps.name("end") {
+                        write!(f, "-{}", end.as_str())?;
+                    }
+                }
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+            _ => (),
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
@@ -0,0 +1,41 @@
+use std::{fmt, str::Split};
+
+use url::Url;
+
+use crate::{handlers::UrlHandler, FancyMarkdownMatched, RenderOptions};
+
+pub(crate) struct Godbolt;
+
+impl UrlHandler for Godbolt {
+    fn hosts(&self) -> &'static str {
+        "godbolt.org"
+    }
+
+    fn description(&self) -> &'static str {
+        "Compiler Explorer sessions"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "godbolt.org"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        _opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        let is_session = match path_segments.next() {
+            Some("z") => path_segments.next().is_some_and(|id| !id.is_empty()),
+            Some("e") => path_segments.next().is_none() && url.fragment().is_some(),
+            _ => false,
+        };
+        if is_session {
+            write!(f, "Compiler Explorer")?;
+            return Ok(FancyMarkdownMatched::Yes);
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
@@ -0,0 +1,43 @@
+use std::{fmt, str::Split};
+
+use itertools::Itertools;
+use url::Url;
+
+use crate::{code, handlers::UrlHandler, FancyMarkdownMatched, RenderOptions};
+
+pub(crate) struct Cts;
+
+impl UrlHandler for Cts {
+    fn hosts(&self) -> &'static str {
+        "gpuweb.github.io"
+    }
+
+    fn description(&self) -> &'static str {
+        "WebGPU CTS test queries"
+    }
+
+    fn uses_query(&self, _url: &Url) -> bool {
+        true
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "gpuweb.github.io"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some(("cts", "standalone", "")) = path_segments.collect_tuple() {
+            if let Some(test_path) = url.query_pairs().find_map(|(k, v)| (k == "q").then_some(v)) {
+                write!(f, "{}", code(opts, test_path))?;
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
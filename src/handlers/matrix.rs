@@ -0,0 +1,64 @@
+use std::{fmt, str::Split};
+
+use url::Url;
+
+use crate::{decode, handlers::UrlHandler, FancyMarkdownMatched, RenderOptions};
+
+pub(crate) struct MatrixTo;
+
+impl UrlHandler for MatrixTo {
+    fn hosts(&self) -> &'static str {
+        "matrix.to"
+    }
+
+    fn description(&self) -> &'static str {
+        "Matrix rooms, messages, and users"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "matrix.to"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        _path_segments: Split<'a, char>,
+        _opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        // Everything interesting is in the fragment, i.e., `#/<room>/<event>?via=<server>`.
+        let Some(permalink) = url.fragment().and_then(|frag| frag.strip_prefix('/')) else {
+            return Ok(FancyMarkdownMatched::No);
+        };
+        let permalink = permalink
+            .split_once('?')
+            .map_or(permalink, |(permalink, _query)| permalink);
+        let mut segments = permalink.split('/').map(decode);
+        let (Some(entity), event, None) = (segments.next(), segments.next(), segments.next())
+        else {
+            return Ok(FancyMarkdownMatched::No);
+        };
+        if event.as_deref().is_some_and(|event| event.starts_with('$')) {
+            if entity.starts_with(['#', '!']) {
+                write!(f, "Matrix message")?;
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        } else if event.is_none() {
+            match entity.chars().next() {
+                Some('#') => {
+                    // Aliases read fine without their homeserver, i.e., `#alias:server.org`.
+                    let alias = entity
+                        .split_once(':')
+                        .map_or(&*entity, |(alias, _server)| alias);
+                    write!(f, "Matrix room {alias}")?
+                }
+                Some('!') => write!(f, "Matrix room")?,
+                Some('@') => write!(f, "Matrix user {entity}")?,
+                _ => return Ok(FancyMarkdownMatched::No),
+            }
+            return Ok(FancyMarkdownMatched::Yes);
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
@@ -0,0 +1,104 @@
+//! Host-specific handlers that turn URLs into readable Markdown link labels.
+
+use std::{fmt, str::Split, sync::OnceLock};
+
+use url::Url;
+
+use crate::{FancyMarkdownMatched, RenderOptions};
+
+mod arxiv;
+mod atlassian;
+mod azure_devops;
+mod file_sharing;
+mod github;
+mod gitlab;
+mod godbolt;
+mod gpuweb;
+mod matrix;
+mod mozilla;
+mod notion;
+mod rust;
+mod stack_exchange;
+mod trello;
+mod vimeo;
+mod zulip;
+
+/// Renders readable labels for URLs of a single service.
+pub trait UrlHandler: Send + Sync {
+    /// A human-readable summary of the hosts this handler matches, e.g., `*.github.io`.
+    fn hosts(&self) -> &'static str;
+
+    /// A short description of what this handler renders, e.g., `pages`.
+    fn description(&self) -> &'static str;
+
+    /// Whether the label this handler writes for `url` depends on its query, or the page it
+    /// points to needs it. If so, the query is kept in link targets even with
+    /// [`RenderOptions::strip_query_on_match`].
+    fn uses_query(&self, _url: &Url) -> bool {
+        false
+    }
+
+    /// A URL that links labeled by this handler should point to instead of `url`, e.g., the web
+    /// page for an API endpoint.
+    fn link_target(&self, _url: &Url, _opts: &RenderOptions) -> Option<Url> {
+        None
+    }
+
+    /// Whether this handler should be tried for URLs with `host`.
+    fn host_matches(&self, host: &str) -> bool;
+
+    /// Writes a label for `url` to `f`, whose path has already been split into
+    /// `path_segments`. Nothing should be written if [`FancyMarkdownMatched::No`] is returned,
+    /// in which case the next matching handler is tried.
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error>;
+}
+
+/// The handlers `mdlink` ships with, in the order they're tried.
+pub fn builtin() -> &'static [Box<dyn UrlHandler>] {
+    static BUILTIN: OnceLock<Vec<Box<dyn UrlHandler>>> = OnceLock::new();
+    BUILTIN.get_or_init(|| {
+        vec![
+            Box::new(github::GitHub),
+            Box::new(github::GitHubApi),
+            Box::new(github::RawGist),
+            Box::new(github::Gist),
+            Box::new(gitlab::GitLab),
+            Box::new(azure_devops::AzureDevOps),
+            Box::new(mozilla::BugzillaShortLink),
+            Box::new(mozilla::Bugzilla),
+            Box::new(mozilla::Phabricator),
+            Box::new(mozilla::Lando),
+            Box::new(rust::CratesIo),
+            Box::new(rust::LibRs),
+            Box::new(rust::DocsRs),
+            Box::new(rust::RustLangDocs),
+            Box::new(rust::Clippy),
+            Box::new(mozilla::Searchfox),
+            Box::new(mozilla::Treeherder),
+            Box::new(gpuweb::Cts),
+            Box::new(mozilla::Pontoon),
+            Box::new(mozilla::FirefoxSourceDocs),
+            Box::new(mozilla::Mdn),
+            Box::new(rust::Playground),
+            Box::new(godbolt::Godbolt),
+            Box::new(notion::Notion),
+            Box::new(arxiv::Arxiv),
+            Box::new(vimeo::Vimeo),
+            Box::new(atlassian::Confluence),
+            Box::new(zulip::Zulip),
+            Box::new(trello::Trello),
+            Box::new(file_sharing::Dropbox),
+            Box::new(file_sharing::GoogleDrive),
+            Box::new(stack_exchange::StackExchange),
+            Box::new(matrix::MatrixTo),
+            // Must come after any other `*.github.io` handlers.
+            Box::new(github::GitHubPages),
+        ]
+    })
+}
@@ -0,0 +1,496 @@
+use std::{
+    fmt::{self, Display},
+    str::Split,
+    sync::OnceLock,
+};
+
+use itertools::Itertools;
+use joinery::JoinableIterator;
+use lazy_format::make_lazy_format;
+use url::Url;
+
+use crate::{
+    alias, code, decode, decode_prose, escape, escape_prose, handlers::UrlHandler,
+    FancyMarkdownMatched, RenderOptions,
+};
+
+pub(crate) struct BugzillaShortLink;
+
+impl UrlHandler for BugzillaShortLink {
+    fn hosts(&self) -> &'static str {
+        "bugzil.la"
+    }
+
+    fn description(&self) -> &'static str {
+        "bugs"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "bugzil.la"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some((bug_id,)) = path_segments.collect_tuple() {
+            render_bugzilla(url, opts, bug_id, f)?;
+            return Ok(FancyMarkdownMatched::Yes);
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct Bugzilla;
+
+impl UrlHandler for Bugzilla {
+    fn hosts(&self) -> &'static str {
+        "bugzilla.mozilla.org"
+    }
+
+    fn description(&self) -> &'static str {
+        "bugs, comments, and bug lists"
+    }
+
+    fn uses_query(&self, _url: &Url) -> bool {
+        true
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "bugzilla.mozilla.org"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        let query_param = |name| {
+            url.query_pairs()
+                .find_map(|(k, v)| (k == name).then_some(v))
+                .filter(|v| !v.is_empty())
+        };
+        match (path_segments.next(), path_segments.next()) {
+            (Some("show_bug.cgi"), None) => {
+                if let Some(bug_id) = query_param("id") {
+                    render_bugzilla(url, opts, bug_id.as_ref(), f)?;
+                    return Ok(FancyMarkdownMatched::Yes);
+                }
+            }
+            (Some("buglist.cgi"), None) => {
+                write!(f, "bug list")?;
+                let product = query_param("product");
+                let component = query_param("component");
+                match (
+                    product.as_deref().map(escape_prose),
+                    component.as_deref().map(escape_prose),
+                ) {
+                    (Some(product), Some(component)) => write!(f, ": {product} / {component}")?,
+                    (Some(product), None) => write!(f, ": {product}")?,
+                    (None, Some(component)) => write!(f, ": {component}")?,
+                    (None, None) => (),
+                }
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+            _ => (),
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct Phabricator;
+
+impl UrlHandler for Phabricator {
+    fn hosts(&self) -> &'static str {
+        "phabricator.services.mozilla.com"
+    }
+
+    fn description(&self) -> &'static str {
+        "Differential revisions and diffs"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "phabricator.services.mozilla.com"
+    }
+
+    fn render<'a>(
+        &self,
+        _url: &'a Url,
+        path_segments: Split<'a, char>,
+        _opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some(("differential", "diff", diff_id)) = path_segments.clone().next_tuple() {
+            if let Some(("",)) = path_segments.collect_tuple() {
+                // extra slash at end, ignore it
+            }
+            write!(f, "diff {diff_id}")?;
+            return Ok(FancyMarkdownMatched::Yes);
+        } else if let Some((id,)) = path_segments.collect_tuple() {
+            if is_differential_revision_id(id) {
+                write!(f, "{id}")?;
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct Lando;
+
+impl UrlHandler for Lando {
+    fn hosts(&self) -> &'static str {
+        "lando.services.mozilla.com"
+    }
+
+    fn description(&self) -> &'static str {
+        "Lando landing requests"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "lando.services.mozilla.com"
+    }
+
+    fn render<'a>(
+        &self,
+        _url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        _opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let (Some(id), Some("") | None, None) = (
+            path_segments.next(),
+            path_segments.next(),
+            path_segments.next(),
+        ) {
+            if is_differential_revision_id(id) {
+                write!(f, "Lando {id}")?;
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct Searchfox;
+
+impl UrlHandler for Searchfox {
+    fn hosts(&self) -> &'static str {
+        "searchfox.org"
+    }
+
+    fn description(&self) -> &'static str {
+        "`mozilla-central` files, symbols, and commits"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "searchfox.org"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        let repo = path_segments
+            .next()
+            .filter(|repo| repo == &"mozilla-central");
+        let history = repo.and_then(|_repo| path_segments.next());
+        if let (Some(repo), Some("commit")) = (repo, history) {
+            if let (Some(hash), Some("") | None) = (path_segments.next(), path_segments.next()) {
+                if !hash.is_empty() {
+                    let sep = &opts.path_separator;
+                    write!(
+                        f,
+                        "{}{sep}{}",
+                        code(opts, alias(opts, repo)),
+                        code(opts, hash)
+                    )?;
+                    return Ok(FancyMarkdownMatched::Yes);
+                }
+            }
+        }
+        let is_moz_central = history.is_some_and(|history| match history {
+            "source" => true,
+            "rev" => {
+                let _rev_hash = path_segments.next();
+                true
+            }
+            _ => false,
+        });
+        if is_moz_central {
+            let file_path = path_segments.map(decode).join_with('/');
+            let is_line_range = |fragment: &str| {
+                fragment.starts_with(|c: char| c.is_ascii_digit())
+                    && fragment.chars().all(|c| c.is_ascii_digit() || c == '-')
+            };
+            match url.fragment() {
+                Some(symbol) if !is_line_range(symbol) => {
+                    write!(f, "{}: {}", code(opts, file_path), code(opts, symbol))?;
+                }
+                line_range => {
+                    let line_range = line_range.unwrap_or_default();
+                    write!(f, "{}:{line_range}", code(opts, file_path))?;
+                }
+            }
+            return Ok(FancyMarkdownMatched::Yes);
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct Treeherder;
+
+impl UrlHandler for Treeherder {
+    fn hosts(&self) -> &'static str {
+        "treeherder.mozilla.org"
+    }
+
+    fn description(&self) -> &'static str {
+        "pushes and repos"
+    }
+
+    fn uses_query(&self, _url: &Url) -> bool {
+        true
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "treeherder.mozilla.org"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some(("jobs",)) = path_segments.collect_tuple() {
+            let mut repo = None;
+            let mut revision = None;
+            for (key, value) in url.query_pairs() {
+                match key.as_ref() {
+                    "repo" => repo = repo.or(Some(value)),
+                    "revision" => revision = revision.or(Some(value)),
+                    _ => (),
+                }
+            }
+
+            match (repo, revision) {
+                (Some(repo), Some(revision)) => {
+                    let repo = escape(alias(opts, &repo));
+                    let revision = revision.get(..12).unwrap_or(revision.as_ref());
+                    let revision = escape(revision);
+                    write!(f, "{}", code(opts, format_args!("{repo}:{revision}")))?;
+                    return Ok(FancyMarkdownMatched::Yes);
+                }
+                (Some(repo), None) => {
+                    write!(f, "treeherder: {}", code(opts, escape(alias(opts, &repo))))?;
+                    return Ok(FancyMarkdownMatched::Yes);
+                }
+                (None, _) => (),
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct Pontoon;
+
+impl UrlHandler for Pontoon {
+    fn hosts(&self) -> &'static str {
+        "pontoon.mozilla.org"
+    }
+
+    fn description(&self) -> &'static str {
+        "localization projects and searches"
+    }
+
+    fn uses_query(&self, _url: &Url) -> bool {
+        true
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "pontoon.mozilla.org"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some((locale, project)) = path_segments.next_tuple() {
+            static LOCALE_RE: OnceLock<regex::Regex> = OnceLock::new();
+            let locale_re = LOCALE_RE
+                .get_or_init(|| regex::Regex::new(r"^[a-z]{2,3}(?:-[A-Za-z0-9]+)*$").unwrap());
+            if locale_re.is_match(locale) && !project.is_empty() {
+                let search = url
+                    .query_pairs()
+                    .find_map(|(k, v)| (k == "search" && !v.is_empty()).then_some(v));
+                let search = make_lazy_format!(|f| match &search {
+                    Some(search) => write!(f, " (search: {})", code(opts, escape(search))),
+                    None => Ok(()),
+                });
+                write!(f, "Pontoon: {locale} {project}{search}")?;
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct FirefoxSourceDocs;
+
+impl UrlHandler for FirefoxSourceDocs {
+    fn hosts(&self) -> &'static str {
+        "firefox-source-docs.mozilla.org"
+    }
+
+    fn description(&self) -> &'static str {
+        "Firefox source documentation pages"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "firefox-source-docs.mozilla.org"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        path_segments: Split<'a, char>,
+        _opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        // An `index.html` page is named by its directory.
+        let page = path_segments
+            .map(|segment| segment.strip_suffix(".html").unwrap_or(segment))
+            .rfind(|segment| !segment.is_empty() && *segment != "index");
+        if let Some(page) = page {
+            write!(f, "Firefox docs: {}", decode_prose(&page.replace('-', " ")))?;
+            if let Some(anchor) = url.fragment().filter(|anchor| !anchor.is_empty()) {
+                write!(f, " §{}", decode_prose(anchor))?;
+            }
+            return Ok(FancyMarkdownMatched::Yes);
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct Mdn;
+
+impl Mdn {
+    /// Splits the path of an MDN page into its locale, if it has one, and the segments of the
+    /// page's path after `docs`.
+    fn locale_and_page<'a>(
+        mut path_segments: Split<'a, char>,
+    ) -> Option<(Option<&'a str>, Split<'a, char>)> {
+        let locale = match path_segments.next()? {
+            "docs" => None,
+            locale => {
+                if path_segments.next()? != "docs" {
+                    return None;
+                }
+                Some(locale)
+            }
+        };
+        Some((locale, path_segments))
+    }
+}
+
+impl UrlHandler for Mdn {
+    fn hosts(&self) -> &'static str {
+        "developer.mozilla.org"
+    }
+
+    fn description(&self) -> &'static str {
+        "MDN docs pages"
+    }
+
+    fn link_target(&self, url: &Url, opts: &RenderOptions) -> Option<Url> {
+        let target_locale = opts.mdn_locale.as_deref()?;
+        let (locale, page_segments) = Self::locale_and_page(url.path_segments()?)?;
+        if locale == Some(target_locale) {
+            return None;
+        }
+        let mut target = url.clone();
+        target.set_path(&format!(
+            "/{target_locale}/docs/{}",
+            page_segments.join_with('/')
+        ));
+        Some(target)
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "developer.mozilla.org"
+    }
+
+    fn render<'a>(
+        &self,
+        _url: &'a Url,
+        path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some((locale, page_segments)) = Self::locale_and_page(path_segments) {
+            let page_segments = page_segments.filter(|segment| !segment.is_empty());
+            if page_segments.clone().next().is_some() {
+                let page = page_segments.map(decode).join_with('/');
+                // Links point to the locale that's overridden to, so that's what's shown.
+                match opts.mdn_locale.as_deref().or(locale) {
+                    Some(locale) => write!(f, "MDN: {page} ({locale})")?,
+                    None => write!(f, "MDN: {page}")?,
+                }
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+/// Checks whether `id` is a Phabricator Differential revision ID, i.e., `D12345`.
+fn is_differential_revision_id(id: &str) -> bool {
+    id.strip_prefix('D')
+        .is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn render_bugzilla(
+    url: &Url,
+    opts: &RenderOptions,
+    bug_id: &str,
+    f: &mut dyn fmt::Write,
+) -> fmt::Result {
+    let bug = make_lazy_format!(|f| if bug_id.chars().all(|c| c.is_ascii_digit()) {
+        write!(f, "bug {bug_id}")
+    } else {
+        write!(f, "{}", code(opts, bug_id))
+    });
+
+    let comment;
+    let mut comment_display: &dyn Display = &"";
+
+    if let Some(fragment) = url.fragment() {
+        if let Some(("", comment_id)) = fragment.split_once('c') {
+            comment = make_lazy_format!(|f| write!(f, ", comment {comment_id}"));
+            comment_display = &comment;
+        }
+    }
+    write!(f, "{bug}{comment_display}")
+}
@@ -0,0 +1,47 @@
+use std::{fmt, str::Split};
+
+use url::Url;
+
+use crate::{decode, handlers::UrlHandler, FancyMarkdownMatched, RenderOptions};
+
+pub(crate) struct Notion;
+
+impl UrlHandler for Notion {
+    fn hosts(&self) -> &'static str {
+        "notion.so"
+    }
+
+    fn description(&self) -> &'static str {
+        "pages"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        matches!(host, "www.notion.so" | "notion.so")
+    }
+
+    fn render<'a>(
+        &self,
+        _url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        _opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some(page) = path_segments.rfind(|segment| !segment.is_empty()) {
+            let title = page
+                .len()
+                .checked_sub(32)
+                .and_then(|id_start| {
+                    let (slug, id) = page.split_at_checked(id_start)?;
+                    id.chars().all(|c| c.is_ascii_hexdigit()).then_some(slug)
+                })
+                .and_then(|slug| slug.strip_suffix('-'))
+                .filter(|title| !title.is_empty());
+            if let Some(title) = title {
+                write!(f, "Notion: {}", decode(title).replace('-', " "))?;
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
@@ -0,0 +1,326 @@
+use std::{
+    fmt::{self, Display},
+    str::Split,
+    sync::OnceLock,
+};
+
+use itertools::Itertools;
+use joinery::JoinableIterator;
+use url::Url;
+
+use crate::{code, handlers::UrlHandler, FancyMarkdownMatched, RenderOptions};
+
+pub(crate) struct CratesIo;
+
+impl UrlHandler for CratesIo {
+    fn hosts(&self) -> &'static str {
+        "crates.io"
+    }
+
+    fn description(&self) -> &'static str {
+        "crate versions"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "crates.io"
+    }
+
+    fn render<'a>(
+        &self,
+        _url: &'a Url,
+        path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some(("crates", crate_name, crate_version)) = path_segments.collect_tuple() {
+            write!(f, "{} v{crate_version}", code(opts, crate_name))?;
+            return Ok(FancyMarkdownMatched::Yes);
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct LibRs;
+
+impl UrlHandler for LibRs {
+    fn hosts(&self) -> &'static str {
+        "lib.rs"
+    }
+
+    fn description(&self) -> &'static str {
+        "crates"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "lib.rs"
+    }
+
+    fn render<'a>(
+        &self,
+        _url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let (Some("crates"), Some(crate_name), Some("") | None, None) = (
+            path_segments.next(),
+            path_segments.next(),
+            path_segments.next(),
+            path_segments.next(),
+        ) {
+            if !crate_name.is_empty() {
+                write!(f, "{} (lib.rs)", code(opts, crate_name))?;
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct DocsRs;
+
+impl UrlHandler for DocsRs {
+    fn hosts(&self) -> &'static str {
+        "docs.rs"
+    }
+
+    fn description(&self) -> &'static str {
+        "crate documentation items"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "docs.rs"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some((crate_pkg_name, _ver, crate_module_name)) = path_segments.next_tuple() {
+            if crate_pkg_name.replace('-', "_") == crate_module_name {
+                match extract_rust_symbol_path(crate_module_name, path_segments, url.fragment()) {
+                    Some(module_path) => {
+                        write!(f, "{}", code(opts, module_path))?;
+                        return Ok(FancyMarkdownMatched::Yes);
+                    }
+                    None => return Ok(FancyMarkdownMatched::No),
+                };
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct RustLangDocs;
+
+impl UrlHandler for RustLangDocs {
+    fn hosts(&self) -> &'static str {
+        "doc.rust-lang.org"
+    }
+
+    fn description(&self) -> &'static str {
+        "standard library documentation items"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "doc.rust-lang.org"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        let crate_module_name = match path_segments.next() {
+            Some("stable" | "beta" | "nightly") => path_segments.next(),
+            first => first,
+        };
+        if let Some(crate_module_name @ ("core" | "alloc" | "std")) = crate_module_name {
+            match extract_rust_symbol_path(crate_module_name, path_segments, url.fragment()) {
+                Some(module_path) => {
+                    write!(f, "{}", code(opts, module_path))?;
+                    return Ok(FancyMarkdownMatched::Yes);
+                }
+                None => return Ok(FancyMarkdownMatched::No),
+            };
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct Clippy;
+
+impl UrlHandler for Clippy {
+    fn hosts(&self) -> &'static str {
+        "rust-lang.github.io"
+    }
+
+    fn description(&self) -> &'static str {
+        "Clippy lints"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "rust-lang.github.io"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        path_segments: Split<'a, char>,
+        opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some(("rust-clippy", release_stage, "index.html")) = path_segments.collect_tuple() {
+            if matches!(release_stage, "stable" | "beta" | "nightly" | "master") {
+                // NOTE: This may be a valid lint name, and we hope so, but we can't
+                // readily distinguish. Older links have a `/` before it.
+                let search_term = url
+                    .fragment()
+                    .map(|frag| frag.strip_prefix('/').unwrap_or(frag))
+                    .filter(|term| !term.is_empty());
+                if let Some(search_term) = search_term {
+                    write!(
+                        f,
+                        "{} in {}",
+                        code(opts, format_args!("clippy::{search_term}")),
+                        code(opts, release_stage),
+                    )?;
+                } else {
+                    write!(
+                        f,
+                        "{} lints in {}",
+                        code(opts, "clippy"),
+                        code(opts, release_stage),
+                    )?;
+                }
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+pub(crate) struct Playground;
+
+impl UrlHandler for Playground {
+    fn hosts(&self) -> &'static str {
+        "play.rust-lang.org"
+    }
+
+    fn description(&self) -> &'static str {
+        "shared Rust Playground gists"
+    }
+
+    fn uses_query(&self, _url: &Url) -> bool {
+        true
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "play.rust-lang.org"
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        path_segments: Split<'a, char>,
+        _opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let Some(("",)) = path_segments.collect_tuple() {
+            let mut gist = None;
+            let mut edition = None;
+            for (key, value) in url.query_pairs() {
+                match key.as_ref() {
+                    "gist" => gist = gist.or(Some(value)),
+                    "edition" => edition = edition.or(Some(value)),
+                    _ => (),
+                }
+            }
+
+            if gist.is_some() {
+                match edition {
+                    Some(edition) => write!(f, "Rust Playground ({edition})")?,
+                    None => write!(f, "Rust Playground")?,
+                }
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+fn extract_rust_symbol_path<'a>(
+    crate_module_name: &'a str,
+    mut path_segments: impl Clone + DoubleEndedIterator<Item = &'a str> + 'a,
+    fragment: Option<&'a str>,
+) -> Option<impl Clone + Display + 'a> {
+    let symbol = match path_segments.next_back() {
+        Some("index.html" | "") | None => None,
+        Some(symbol) => {
+            static SYMBOL_RE: OnceLock<regex::Regex> = OnceLock::new();
+            let symbol_re = SYMBOL_RE.get_or_init(|| {
+                regex::Regex::new(concat!(
+                    "(?P<symbol_kind>constant|struct|fn|enum|trait|attr|primitive|type)",
+                    r"\.",
+                    r"(?P<ident>\w+)",
+                    r"\.html"
+                ))
+                .unwrap()
+            });
+            match symbol_re.captures(symbol).map(|caps| {
+                let group = |name| caps.name(name).unwrap().as_str();
+                (group("symbol_kind"), group("ident"))
+            }) {
+                None => return None,
+                some => some,
+            }
+        }
+    };
+
+    let fragment = match fragment {
+        None => None,
+        Some(fragment) => {
+            static FRAGMENT_CAPS: OnceLock<regex::Regex> = OnceLock::new();
+            let fragment_re = FRAGMENT_CAPS.get_or_init(|| {
+                regex::Regex::new(concat!(
+                    "(tymethod|method|associatedconstant|structfield)",
+                    r"\.",
+                    r"(?P<ident>\w+)"
+                ))
+                .unwrap()
+            });
+            fragment_re
+                .captures(fragment)
+                .map(|caps| caps.name("ident").unwrap().as_str())
+        }
+    };
+
+    let mut symbol_name = None;
+    let mut crate_module_name = Some(crate_module_name);
+    if let Some((kind, name)) = symbol {
+        symbol_name = Some(name);
+        if kind == "primitive" {
+            crate_module_name = None
+        }
+    }
+
+    Some(
+        crate_module_name
+            .into_iter()
+            .chain(path_segments)
+            .chain(symbol_name)
+            .chain(fragment)
+            .join_with("::"),
+    )
+}
@@ -0,0 +1,69 @@
+use std::{borrow::Cow, fmt, str::Split};
+
+use url::Url;
+
+use crate::{decode_prose, handlers::UrlHandler, FancyMarkdownMatched, RenderOptions};
+
+/// Sites of the Stack Exchange network, which all share the same URL structure.
+pub(crate) struct StackExchange;
+
+impl StackExchange {
+    fn site_name(host: &str) -> Option<Cow<'_, str>> {
+        let host = host.strip_prefix("www.").unwrap_or(host);
+        let name = match host {
+            "stackoverflow.com" => "StackOverflow",
+            "superuser.com" => "SuperUser",
+            "serverfault.com" => "ServerFault",
+            "askubuntu.com" => "AskUbuntu",
+            "mathoverflow.net" => "MathOverflow",
+            "stackapps.com" => "StackApps",
+            _ => {
+                // `unix.stackexchange.com` is `Unix.SE`, etc.
+                let site = host
+                    .strip_suffix(".stackexchange.com")
+                    .filter(|site| !site.is_empty() && !site.contains('.'))?;
+                let mut chars = site.chars();
+                let first = chars.next()?.to_uppercase();
+                return Some(Cow::Owned(format!("{first}{}.SE", chars.as_str())));
+            }
+        };
+        Some(Cow::Borrowed(name))
+    }
+}
+
+impl UrlHandler for StackExchange {
+    fn hosts(&self) -> &'static str {
+        "stackoverflow.com, *.stackexchange.com, etc."
+    }
+
+    fn description(&self) -> &'static str {
+        "questions on Stack Exchange network sites"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        Self::site_name(host).is_some()
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        _opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        let Some(site) = url.host_str().and_then(StackExchange::site_name) else {
+            return Ok(FancyMarkdownMatched::No);
+        };
+        if let (Some("questions"), Some(id)) = (path_segments.next(), path_segments.next()) {
+            if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) {
+                match path_segments.next().filter(|slug| !slug.is_empty()) {
+                    Some(slug) => write!(f, "{site}: {}", decode_prose(&slug.replace('-', " ")))?,
+                    None => write!(f, "{site} question {id}")?,
+                }
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
@@ -0,0 +1,51 @@
+use std::{fmt, str::Split};
+
+use itertools::Itertools;
+use url::Url;
+
+use crate::{decode_prose, handlers::UrlHandler, FancyMarkdownMatched, RenderOptions};
+
+pub(crate) struct Trello;
+
+impl UrlHandler for Trello {
+    fn hosts(&self) -> &'static str {
+        "trello.com"
+    }
+
+    fn description(&self) -> &'static str {
+        "cards and boards"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "trello.com"
+    }
+
+    fn render<'a>(
+        &self,
+        _url: &'a Url,
+        path_segments: Split<'a, char>,
+        _opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        match path_segments.collect_tuple() {
+            Some(("c", _card_id, slug)) => {
+                // Card slugs are prefixed with the card's number, i.e., `45-card-title`.
+                let title = slug
+                    .split_once('-')
+                    .filter(|(num, _title)| num.chars().all(|c| c.is_ascii_digit()))
+                    .map_or(slug, |(_num, title)| title);
+                if !title.is_empty() {
+                    write!(f, "Trello: {}", decode_prose(&title.replace('-', " ")))?;
+                    return Ok(FancyMarkdownMatched::Yes);
+                }
+            }
+            Some(("b", _board_id, slug)) if !slug.is_empty() => {
+                write!(f, "Trello board: {}", decode_prose(&slug.replace('-', " ")))?;
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+            _ => (),
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
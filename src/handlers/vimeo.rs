@@ -0,0 +1,79 @@
+use std::{fmt, str::Split};
+
+use url::Url;
+
+use crate::{handlers::UrlHandler, FancyMarkdownMatched, RenderOptions};
+
+pub(crate) struct Vimeo;
+
+impl UrlHandler for Vimeo {
+    fn hosts(&self) -> &'static str {
+        "vimeo.com"
+    }
+
+    fn description(&self) -> &'static str {
+        "videos, with timestamps"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        matches!(host, "vimeo.com" | "www.vimeo.com")
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        mut path_segments: Split<'a, char>,
+        _opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        if let (Some(video_id), Some("") | None, None) = (
+            path_segments.next(),
+            path_segments.next(),
+            path_segments.next(),
+        ) {
+            if !video_id.is_empty() && video_id.chars().all(|c| c.is_ascii_digit()) {
+                write!(f, "Vimeo {video_id}")?;
+                let timestamp = url
+                    .fragment()
+                    .and_then(|frag| frag.strip_prefix("t="))
+                    .and_then(parse_timestamp);
+                if let Some(secs) = timestamp {
+                    let (hours, mins, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+                    if hours > 0 {
+                        write!(f, " at {hours}:{mins:02}:{secs:02}")?;
+                    } else {
+                        write!(f, " at {mins}:{secs:02}")?;
+                    }
+                }
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+/// Parses a timestamp like `90`, `90s`, or `1h2m30s` into seconds.
+fn parse_timestamp(timestamp: &str) -> Option<u64> {
+    if let Ok(secs) = timestamp.parse() {
+        return Some(secs);
+    }
+
+    let mut total = 0u64;
+    let mut rest = timestamp;
+    while !rest.is_empty() {
+        let unit_idx = rest.find(|c: char| !c.is_ascii_digit())?;
+        let (num, unit) = rest.split_at(unit_idx);
+        let num = num.parse::<u64>().ok()?;
+        let (unit, remaining) = unit.split_at(1);
+        let multiplier = match unit {
+            "h" => 3600,
+            "m" => 60,
+            "s" => 1,
+            _ => return None,
+        };
+        total = total.checked_add(num.checked_mul(multiplier)?)?;
+        rest = remaining;
+    }
+    (!timestamp.is_empty()).then_some(total)
+}
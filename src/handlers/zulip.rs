@@ -0,0 +1,63 @@
+use std::{borrow::Cow, fmt, str::Split};
+
+use url::Url;
+
+use crate::{decode_prose, handlers::UrlHandler, FancyMarkdownMatched, RenderOptions};
+
+pub(crate) struct Zulip;
+
+impl UrlHandler for Zulip {
+    fn hosts(&self) -> &'static str {
+        "chat.mozilla.org, *.zulipchat.com"
+    }
+
+    fn description(&self) -> &'static str {
+        "Zulip streams and topics"
+    }
+
+    fn host_matches(&self, host: &str) -> bool {
+        host == "chat.mozilla.org" || host.ends_with(".zulipchat.com")
+    }
+
+    fn render<'a>(
+        &self,
+        url: &'a Url,
+        _path_segments: Split<'a, char>,
+        _opts: &RenderOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<FancyMarkdownMatched, fmt::Error> {
+        let Some(narrow) = url.fragment().and_then(|frag| frag.strip_prefix("narrow/")) else {
+            return Ok(FancyMarkdownMatched::No);
+        };
+        let mut narrow_segments = narrow.split('/');
+        if let (Some("stream" | "channel"), Some(stream)) =
+            (narrow_segments.next(), narrow_segments.next())
+        {
+            // Streams are identified as `<id>-<name>`.
+            let stream = stream
+                .split_once('-')
+                .filter(|(id, _name)| id.chars().all(|c| c.is_ascii_digit()))
+                .map_or(stream, |(_id, name)| name);
+            if !stream.is_empty() {
+                write!(f, "Zulip: {}", decode_narrow_operand(stream))?;
+                if let (Some("topic"), Some(topic)) =
+                    (narrow_segments.next(), narrow_segments.next())
+                {
+                    write!(f, " > {}", decode_narrow_operand(topic))?;
+                }
+                return Ok(FancyMarkdownMatched::Yes);
+            }
+        }
+
+        Ok(FancyMarkdownMatched::No)
+    }
+}
+
+/// Decodes an operand of a Zulip narrow fragment, which is percent-encoded with `.` in place of
+/// `%` (i.e., `Some.20Topic` is `Some Topic`), for display in prose.
+fn decode_narrow_operand(operand: &str) -> Cow<'_, str> {
+    if !operand.contains('.') {
+        return decode_prose(operand);
+    }
+    Cow::Owned(decode_prose(&operand.replace('.', "%")).into_owned())
+}
@@ -0,0 +1,761 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fmt::{self, Display, Write as _},
+};
+
+use itertools::Itertools;
+use lazy_format::make_lazy_format;
+use percent_encoding::percent_decode_str;
+use url::Url;
+
+pub mod handlers;
+
+/// Options that affect how URLs are matched and rendered.
+#[derive(Debug)]
+pub struct RenderOptions {
+    /// Hosts whose URLs are never matched, even if a handler exists for them.
+    pub disabled_hosts: Vec<String>,
+    /// Additional hosts to treat as GitHub Enterprise Server instances.
+    pub github_hosts: Vec<String>,
+    /// Whether repo names, file paths, symbols, etc. are wrapped in backticks to render as
+    /// inline code.
+    pub use_backticks: bool,
+    /// The separator written between a repo, its commit-ish, and a file path, i.e., the `:`s
+    /// in `` `org/repo`:`main`:`src/lib.rs` ``.
+    pub path_separator: Cow<'static, str>,
+    /// A branch name to leave out of GitHub file, directory, and history links, i.e.,
+    /// `` `org/repo`:`src/lib.rs` `` instead of `` `org/repo`:`main`:`src/lib.rs` `` when this
+    /// is `main`.
+    pub hide_default_branch: Option<String>,
+    /// Looks up the titles of linked GitHub issues and PRs, which are appended to their labels.
+    pub issue_titles: Option<IssueTitleLookup>,
+    /// Looks up the subject lines of linked GitHub commits, which are appended to their labels.
+    pub commit_subjects: Option<CommitSubjectLookup>,
+    /// Labels for specific URLs, which take priority over every handler. URLs must match
+    /// exactly.
+    pub link_table: BTreeMap<String, String>,
+    /// Whether labels written by handlers are suffixed with the URL's host, i.e.,
+    /// `` `org/repo`#42 (github.com) ``. Labels from [`Self::link_table`] are used as-is.
+    pub annotate_host: bool,
+    /// Whether the query is removed from the targets of links whose labels came from a
+    /// handler that doesn't use it (see [`handlers::UrlHandler::uses_query`]).
+    pub strip_query_on_match: bool,
+    /// Hosts that serve images, whose URLs are rendered as Markdown images (`![](url)`) or HTML
+    /// `<img>`s rather than links.
+    pub image_hosts: Vec<String>,
+    /// Whether version-like tags (e.g., `v1.2.3`) in GitHub file, directory, and history links
+    /// are marked as such, i.e., `` `org/repo`@`v1.2.3`:`src/lib.rs` ``. Other refs are
+    /// unaffected.
+    pub mark_tag_refs: bool,
+    /// A GitHub org whose repos are labeled without it, i.e., `` `repo`#42 `` instead of
+    /// `` `org/repo`#42 ``.
+    pub home_org: Option<String>,
+    /// How much of the name of a GitHub repo is shown in its labels, unless it's in
+    /// [`Self::home_org`] or has one of [`Self::aliases`].
+    pub repo_prefix: RepoPrefix,
+    /// How much of the path of a `file:` URL is shown in its label.
+    pub file_url_display: FileUrlDisplay,
+    /// The label of GitHub issue and PR links, in which `{prefix}` is replaced with the repo
+    /// (i.e., `` `org/repo` ``), `{num}` with the issue number, and `{url}` with the URL. Labels
+    /// of issues in a well-known series (e.g., `RFC #42`) and of issue sub-pages are unaffected.
+    pub issue_format: Cow<'static, str>,
+    /// Names to display for repos and projects, keyed by the name they'd otherwise be shown with
+    /// (e.g., `mozilla-central` or, for GitHub, `org/repo`).
+    pub aliases: BTreeMap<String, String>,
+    /// A locale (e.g., `en-US`) that MDN links point to instead of the one in their URL. Labels
+    /// show the locale that's linked to.
+    pub mdn_locale: Option<String>,
+}
+
+/// A function that looks up the title of a GitHub issue or PR, given a key like `org/repo#42`.
+/// It's called during rendering, so it should be cheap after the first call.
+pub struct IssueTitleLookup(pub Box<IssueTitleFn>);
+
+/// See [`IssueTitleLookup`].
+pub type IssueTitleFn = dyn Fn(&str) -> Option<String> + Send + Sync;
+
+impl fmt::Debug for IssueTitleLookup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IssueTitleLookup").finish_non_exhaustive()
+    }
+}
+
+/// A function that looks up the subject line of a commit, given its repo (i.e., `org/repo`) and
+/// SHA. It's called during rendering, so it should be cheap after the first call for a commit.
+pub struct CommitSubjectLookup(pub Box<CommitSubjectFn>);
+
+/// See [`CommitSubjectLookup`].
+pub type CommitSubjectFn = dyn Fn(&str, &str) -> Option<String> + Send + Sync;
+
+impl fmt::Debug for CommitSubjectLookup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CommitSubjectLookup").finish_non_exhaustive()
+    }
+}
+
+/// How much of the name of a GitHub repo is shown in its labels.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RepoPrefix {
+    /// The org and the repo, i.e., `org/repo`.
+    #[default]
+    Full,
+    /// Only the repo, i.e., `repo`.
+    RepoOnly,
+}
+
+/// How much of the path of a `file:` URL is shown in its label.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FileUrlDisplay {
+    /// Only the file name, i.e., `main.rs`.
+    #[default]
+    Basename,
+    /// The whole path, i.e., `/home/user/project/src/main.rs`.
+    FullPath,
+}
+
+impl RenderOptions {
+    const DEFAULT: Self = Self {
+        disabled_hosts: Vec::new(),
+        github_hosts: Vec::new(),
+        use_backticks: true,
+        path_separator: Cow::Borrowed(":"),
+        hide_default_branch: None,
+        issue_titles: None,
+        commit_subjects: None,
+        link_table: BTreeMap::new(),
+        annotate_host: false,
+        strip_query_on_match: false,
+        image_hosts: Vec::new(),
+        mark_tag_refs: false,
+        home_org: None,
+        repo_prefix: RepoPrefix::Full,
+        file_url_display: FileUrlDisplay::Basename,
+        issue_format: Cow::Borrowed("{prefix}#{num}"),
+        aliases: BTreeMap::new(),
+        mdn_locale: None,
+    };
+
+    fn is_disabled_host(&self, host: &str) -> bool {
+        self.disabled_hosts.iter().any(|disabled| disabled == host)
+    }
+
+    fn is_image_host(&self, url: &Url) -> bool {
+        url.host_str()
+            .is_some_and(|host| self.image_hosts.iter().any(|image_host| image_host == host))
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static DEFAULT_RENDER_OPTIONS: RenderOptions = RenderOptions::DEFAULT;
+
+/// Whether a URL was recognized by one of `mdlink`'s handlers.
+#[derive(Clone, Copy, Debug)]
+pub enum FancyMarkdownMatched {
+    Yes,
+    No,
+}
+
+/// Renders `url` with default [`RenderOptions`]. See [`RenderedLink`] for details.
+pub fn render(url: &Url) -> RenderedLink<'_> {
+    RenderedLink::new(url, &DEFAULT_RENDER_OPTIONS)
+}
+
+/// Lazily renders each of `urls` with default [`RenderOptions`], like [`render`].
+pub fn render_all<I: IntoIterator<Item = Url>>(urls: I) -> impl Iterator<Item = String> {
+    urls.into_iter().map(|url| render(&url).to_string())
+}
+
+/// A URL that [`Display`]s as a Markdown link with a readable label if it's recognized, or
+/// as an autolink (`<url>`) otherwise. Unrecognized URLs with schemes other than `http`,
+/// `https`, and `mailto` are rendered as inline code instead of an autolink.
+#[derive(Clone, Copy, Debug)]
+pub struct RenderedLink<'a> {
+    url: &'a Url,
+    opts: &'a RenderOptions,
+}
+
+impl<'a> RenderedLink<'a> {
+    pub fn new(url: &'a Url, opts: &'a RenderOptions) -> Self {
+        Self { url, opts }
+    }
+}
+
+impl Display for RenderedLink<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { url, opts } = self;
+        if opts.is_image_host(url) {
+            return write!(f, "![]({url})");
+        }
+        match try_write_markdown_url(url, opts, &mut *f)? {
+            FancyMarkdownMatched::Yes => Ok(()),
+            FancyMarkdownMatched::No => match url.scheme() {
+                "http" | "https" | "mailto" => write!(f, "<{url}>"),
+                // Internal browser pages (`about:config`, `chrome://…`, `moz-extension://…`,
+                // etc.) aren't useful as autolinks, so show them as code instead.
+                _ => write!(f, "{}", fenced_code(opts, url.as_str())),
+            },
+        }
+    }
+}
+
+/// Writes `url` as a Markdown link with a readable label, if it's recognized. Nothing is
+/// written if it isn't.
+pub fn try_write_markdown_url(
+    url: &Url,
+    opts: &RenderOptions,
+    mut f: impl fmt::Write,
+) -> Result<FancyMarkdownMatched, fmt::Error> {
+    let mut label = String::new();
+    match write_label(url, opts, &mut label)? {
+        Some(source) => {
+            write!(f, "[{label}]({})", link_target(url, opts, &source))?;
+            Ok(FancyMarkdownMatched::Yes)
+        }
+        None => Ok(FancyMarkdownMatched::No),
+    }
+}
+
+/// Writes `url` as an HTML anchor (`<a href="url">label</a>`), with a readable label if it's
+/// recognized, or the URL itself otherwise. Both are HTML-escaped.
+pub fn write_html_anchor(url: &Url, opts: &RenderOptions, mut f: impl fmt::Write) -> fmt::Result {
+    if opts.is_image_host(url) {
+        return write!(f, "<img src=\"{}\">", html_escape(url.as_str()));
+    }
+    let mut label = String::new();
+    let target = match write_label(url, opts, &mut label)? {
+        Some(source) => link_target(url, opts, &source),
+        None => {
+            label = url.to_string();
+            Cow::Borrowed(url)
+        }
+    };
+    write!(
+        f,
+        "<a href=\"{}\">{}</a>",
+        html_escape(target.as_str()),
+        html_escape(&label)
+    )?;
+    Ok(())
+}
+
+fn html_escape(text: &str) -> impl Display + '_ {
+    make_lazy_format!(|f| {
+        for c in text.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Writes only the readable label for `url`, if it's recognized. Nothing is written if it
+/// isn't.
+pub fn try_write_label(
+    url: &Url,
+    opts: &RenderOptions,
+    f: impl fmt::Write,
+) -> Result<FancyMarkdownMatched, fmt::Error> {
+    Ok(match write_label(url, opts, f)? {
+        Some(_source) => FancyMarkdownMatched::Yes,
+        None => FancyMarkdownMatched::No,
+    })
+}
+
+/// What a label written by [`write_label`] came from.
+enum LabelSource {
+    /// A [`RenderOptions::link_table`] entry, or a label derived from a URL with a scheme other
+    /// than `http` or `https`.
+    Fixed,
+    Handler(&'static dyn handlers::UrlHandler),
+}
+
+fn write_label(
+    url: &Url,
+    opts: &RenderOptions,
+    mut f: impl fmt::Write,
+) -> Result<Option<LabelSource>, fmt::Error> {
+    if let Some(label) = opts.link_table.get(url.as_str()) {
+        f.write_str(label)?;
+        return Ok(Some(LabelSource::Fixed));
+    }
+    if let "mailto" | "tel" = url.scheme() {
+        // The address or number is the path, with any query (e.g., `?subject=…`) left out.
+        let address = decode(url.path());
+        if !address.is_empty() {
+            f.write_str(&address)?;
+            return Ok(Some(LabelSource::Fixed));
+        }
+    }
+    if url.scheme() == "file" {
+        let path = decode(url.path());
+        let label = match opts.file_url_display {
+            FileUrlDisplay::Basename => path.rsplit('/').find(|segment| !segment.is_empty()),
+            FileUrlDisplay::FullPath => None,
+        };
+        write!(f, "{}", code(opts, label.unwrap_or(&path)))?;
+        return Ok(Some(LabelSource::Fixed));
+    }
+    if let "http" | "https" = url.scheme() {
+        if let Some(actual_host) = url.host_str() {
+            let host = actual_host;
+            if opts.is_disabled_host(host) {
+                return Ok(None);
+            }
+            let host = if opts
+                .github_hosts
+                .iter()
+                .any(|github_host| github_host == host)
+            {
+                // GitHub Enterprise Server instances share `github.com`'s URL structure.
+                "github.com"
+            } else {
+                host
+            };
+            let Some(path_segments) = url.path_segments() else {
+                return Ok(None);
+            };
+            for handler in handlers::builtin() {
+                if handler.host_matches(host) {
+                    let matched = handler.render(url, path_segments.clone(), opts, &mut f)?;
+                    if let FancyMarkdownMatched::Yes = matched {
+                        if opts.annotate_host {
+                            // Name the host the link goes to, which may not be the input's.
+                            let target = handler.link_target(url, opts);
+                            let target_host = target.as_ref().and_then(Url::host_str);
+                            write!(f, " ({})", target_host.unwrap_or(actual_host))?;
+                        }
+                        return Ok(Some(LabelSource::Handler(&**handler)));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// The URL a link labeled from `source` should point to. This is `url`, unless the handler
+/// redirects it elsewhere or [`RenderOptions::strip_query_on_match`] applies.
+fn link_target<'a>(url: &'a Url, opts: &RenderOptions, source: &LabelSource) -> Cow<'a, Url> {
+    let LabelSource::Handler(handler) = source else {
+        return Cow::Borrowed(url);
+    };
+    let mut target = handler
+        .link_target(url, opts)
+        .map_or(Cow::Borrowed(url), Cow::Owned);
+    if opts.strip_query_on_match && !handler.uses_query(url) && target.query().is_some() {
+        target.to_mut().set_query(None);
+    }
+    target
+}
+
+/// Percent-decodes a URL path segment for display. Invalid UTF-8 is replaced, rather than
+/// rejected, since the result is only ever shown. Characters that are significant in Markdown
+/// link labels and code spans (`` ` ``, `[`, and `]`) are left encoded, so labels stay
+/// well-formed.
+pub(crate) fn decode(segment: &str) -> Cow<'_, str> {
+    let decoded = percent_decode_str(segment).decode_utf8_lossy();
+    if let Cow::Owned(escaped) = escape(&decoded) {
+        return Cow::Owned(escaped);
+    }
+    decoded
+}
+
+/// Percent-decodes a URL path segment for display in prose, like [`decode`], but with the
+/// characters that are significant in link labels backslash-escaped by [`escape_prose`] instead.
+pub(crate) fn decode_prose(segment: &str) -> Cow<'_, str> {
+    let decoded = percent_decode_str(segment).decode_utf8_lossy();
+    if let Cow::Owned(escaped) = escape_prose(&decoded) {
+        return Cow::Owned(escaped);
+    }
+    decoded
+}
+
+/// Percent-encodes the characters of already-decoded `text` (e.g., a query parameter) that
+/// [`decode`] leaves encoded, so it can be written into a label. This is meant for text in code
+/// spans, where backslash escapes don't work; other text should use [`escape_prose`].
+pub(crate) fn escape(text: &str) -> Cow<'_, str> {
+    if !text.contains(['`', '[', ']']) {
+        return Cow::Borrowed(text);
+    }
+    let mut encoded = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '`' => encoded.push_str("%60"),
+            '[' => encoded.push_str("%5B"),
+            ']' => encoded.push_str("%5D"),
+            c => encoded.push(c),
+        }
+    }
+    Cow::Owned(encoded)
+}
+
+/// Backslash-escapes the characters of `text` (e.g., an issue title) that are significant in
+/// Markdown link labels, so it can be written into a label as readable prose.
+pub(crate) fn escape_prose(text: &str) -> Cow<'_, str> {
+    if !text.contains(['`', '[', ']', '\\']) {
+        return Cow::Borrowed(text);
+    }
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if let '`' | '[' | ']' | '\\' = c {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    Cow::Owned(escaped)
+}
+
+/// The name to display for the repo or project `name`; see [`RenderOptions::aliases`].
+pub(crate) fn alias<'a>(opts: &'a RenderOptions, name: &'a str) -> &'a str {
+    opts.aliases.get(name).map_or(name, String::as_str)
+}
+
+/// Renders `text` as inline code, unless backticks are disabled in `opts`.
+pub(crate) fn code<'a>(opts: &'a RenderOptions, text: impl Display + 'a) -> impl Display + 'a {
+    make_lazy_format!(|f| if opts.use_backticks {
+        write!(f, "`{text}`")
+    } else {
+        write!(f, "{text}")
+    })
+}
+
+/// Renders `text` as inline code like [`code`], but fenced with enough backticks that any in
+/// `text` (e.g., in opaque URLs, which don't percent-encode them) don't end it early.
+fn fenced_code<'a>(opts: &'a RenderOptions, text: &'a str) -> impl Display + 'a {
+    make_lazy_format!(|f| {
+        let longest_run = text
+            .split(|c| c != '`')
+            .map(str::len)
+            .max()
+            .unwrap_or_default();
+        if !opts.use_backticks || longest_run == 0 {
+            return write!(f, "{}", code(opts, text));
+        }
+        let fence = "`".repeat(longest_run + 1);
+        // The spaces keep backticks at either end of `text` from being read as part of the fence.
+        // One is stripped from each end when rendered.
+        write!(f, "{fence} {text} {fence}")
+    })
+}
+
+/// Checks whether `url` would be rendered as a fancy Markdown link, without building its label.
+pub fn is_recognized(url: &Url) -> bool {
+    is_recognized_with(url, &DEFAULT_RENDER_OPTIONS)
+}
+
+/// Like [`is_recognized`], but with custom [`RenderOptions`]. URLs embedded as images (see
+/// [`RenderOptions::image_hosts`]) count as recognized.
+pub fn is_recognized_with(url: &Url, opts: &RenderOptions) -> bool {
+    /// A sink that fails on the first write, which stops rendering as soon as a handler
+    /// starts writing a label.
+    struct StopOnWrite;
+
+    impl fmt::Write for StopOnWrite {
+        fn write_str(&mut self, _s: &str) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    // Images are embedded rather than labeled, but they're still rendered specially.
+    if opts.is_image_host(url) {
+        return true;
+    }
+    match try_write_label(url, opts, StopOnWrite) {
+        Ok(FancyMarkdownMatched::Yes) | Err(fmt::Error) => true,
+        Ok(FancyMarkdownMatched::No) => false,
+    }
+}
+
+/// Removes well-known tracking parameters (`utm_*`, `fbclid`, `gclid`, etc.) from `url`'s
+/// query. Parameters that are kept stay in their original order and encoding.
+pub fn trim_tracking_params(url: &mut Url) {
+    const TRACKING_PARAMS: &[&str] = &[
+        "_hsenc", "_hsmi", "dclid", "fbclid", "gclid", "igshid", "mc_cid", "mc_eid", "msclkid",
+        "yclid",
+    ];
+
+    let Some(query) = url.query() else {
+        return;
+    };
+    let kept = query
+        .split('&')
+        .filter(|param| {
+            let key = param.split_once('=').map_or(*param, |(key, _value)| key);
+            !(key.starts_with("utm_") || TRACKING_PARAMS.contains(&key))
+        })
+        .join("&");
+    if kept != query {
+        url.set_query((!kept.is_empty()).then_some(&kept));
+    }
+}
+
+/// Rewrites `url` into the form that's usually linked to. A leading `www.` is removed from its
+/// host, and `git` remotes (e.g., `ssh://git@github.com/org/repo.git`) become the web page of
+/// their repo (`https://github.com/org/repo`).
+pub fn normalize(url: &mut Url) {
+    if let "ssh" | "git" | "git+ssh" = url.scheme() {
+        // The user and port are only meaningful for `git`.
+        let web_url = url
+            .host_str()
+            .and_then(|host| Url::parse(&format!("https://{host}{}", url.path())).ok());
+        match web_url {
+            Some(web_url) => *url = web_url,
+            None => return,
+        }
+    }
+    if !matches!(url.scheme(), "http" | "https") {
+        return;
+    }
+    if let Some(host) = url.host_str().and_then(|host| host.strip_prefix("www.")) {
+        let host = host.to_owned();
+        // The rest may not be a valid host on its own (e.g., of `www.`), in which case the host
+        // is left alone.
+        let _ = url.set_host(Some(&host));
+    }
+    if let Some(path) = url.path().strip_suffix(".git") {
+        if !path.ends_with('/') {
+            let path = path.to_owned();
+            url.set_path(&path);
+        }
+    }
+}
+
+/// Parses an scp-like `git` remote (e.g., `git@github.com:org/repo.git`), which isn't a URL,
+/// into the `ssh://` URL it stands for. [`normalize`] turns that into a web page.
+pub fn parse_scp_like_remote(remote: &str) -> Option<Url> {
+    let (user_host, path) = remote.split_once(':')?;
+    // URLs can't have an `@` before their scheme's `:`, so this can't be mistaken for one.
+    let (user, host) = user_host.split_once('@')?;
+    if user.is_empty() || host.is_empty() || host.contains('/') || path.starts_with("//") {
+        return None;
+    }
+    Url::parse(&format!(
+        "ssh://{user_host}/{}",
+        path.trim_start_matches('/')
+    ))
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::{
+        is_recognized_with, normalize, parse_scp_like_remote, trim_tracking_params,
+        write_html_anchor, RenderOptions,
+    };
+
+    fn normalized(url: &str) -> String {
+        let mut url = Url::parse(url).unwrap();
+        normalize(&mut url);
+        url.into()
+    }
+
+    #[test]
+    fn normalize_removes_www() {
+        assert_eq!(
+            normalized("https://www.example.com/a?b=c"),
+            "https://example.com/a?b=c",
+        );
+        assert_eq!(
+            normalized("https://wwwexample.com/"),
+            "https://wwwexample.com/"
+        );
+        assert_eq!(normalized("https://www./"), "https://www./");
+    }
+
+    #[test]
+    fn normalize_turns_git_remotes_into_web_pages() {
+        assert_eq!(
+            normalized("ssh://git@github.com/org/repo.git"),
+            "https://github.com/org/repo",
+        );
+        assert_eq!(
+            normalized("git+ssh://git@gitlab.com:2222/group/sub/repo.git"),
+            "https://gitlab.com/group/sub/repo",
+        );
+        assert_eq!(
+            normalized("git://github.com/org/repo"),
+            "https://github.com/org/repo",
+        );
+        assert_eq!(
+            normalized("https://github.com/org/repo.git"),
+            "https://github.com/org/repo",
+        );
+    }
+
+    #[test]
+    fn normalize_leaves_other_urls_alone() {
+        assert_eq!(
+            normalized("https://example.com/.git"),
+            "https://example.com/.git"
+        );
+        assert_eq!(
+            normalized("mailto:a@www.example.com"),
+            "mailto:a@www.example.com"
+        );
+        assert_eq!(normalized("about:config"), "about:config");
+    }
+
+    #[test]
+    fn parse_scp_like_remote_of_remotes() {
+        assert_eq!(
+            parse_scp_like_remote("git@github.com:org/repo.git").map(String::from),
+            Some("ssh://git@github.com/org/repo.git".to_owned()),
+        );
+        assert_eq!(parse_scp_like_remote("https://github.com/org/repo"), None);
+        assert_eq!(parse_scp_like_remote("mailto:a@b.c"), None);
+        assert_eq!(parse_scp_like_remote("git@github.com"), None);
+    }
+
+    fn trimmed(url: &str) -> String {
+        let mut url = Url::parse(url).unwrap();
+        trim_tracking_params(&mut url);
+        url.into()
+    }
+
+    #[test]
+    fn trim_tracking_params_keeps_order_of_kept_params() {
+        assert_eq!(
+            trimmed("https://example.com/?b=2&utm_source=x&a=1&fbclid=y&c=3"),
+            "https://example.com/?b=2&a=1&c=3",
+        );
+    }
+
+    #[test]
+    fn trim_tracking_params_handles_bare_keys() {
+        assert_eq!(
+            trimmed("https://example.com/?flag&gclid&utm_medium"),
+            "https://example.com/?flag",
+        );
+    }
+
+    #[test]
+    fn trim_tracking_params_removes_emptied_query() {
+        assert_eq!(
+            trimmed("https://example.com/page?utm_source=a&utm_campaign=b&msclkid=c"),
+            "https://example.com/page",
+        );
+    }
+
+    #[test]
+    fn trim_tracking_params_removes_any_utm_param() {
+        assert_eq!(
+            trimmed("https://example.com/?utm_whatever=1&utm_=2&utmost=3"),
+            "https://example.com/?utmost=3",
+        );
+    }
+
+    #[test]
+    fn trim_tracking_params_leaves_untracked_urls_alone() {
+        assert_eq!(
+            trimmed("https://example.com/?q=a%20b&x=1"),
+            "https://example.com/?q=a%20b&x=1",
+        );
+        assert_eq!(trimmed("https://example.com/"), "https://example.com/");
+    }
+
+    fn html_anchor(url: &str, opts: &RenderOptions) -> String {
+        let mut anchor = String::new();
+        write_html_anchor(&Url::parse(url).unwrap(), opts, &mut anchor).unwrap();
+        anchor
+    }
+
+    #[test]
+    fn write_html_anchor_escapes_labels() {
+        assert_eq!(
+            html_anchor(
+                "https://github.com/search?q=a%3Cb+%26+c",
+                &RenderOptions::default()
+            ),
+            "<a href=\"https://github.com/search?q=a%3Cb+%26+c\">GitHub search: a&lt;b &amp; c</a>",
+        );
+    }
+
+    #[test]
+    fn write_html_anchor_escapes_query_separators_in_targets() {
+        assert_eq!(
+            html_anchor(
+                "https://github.com/search?q=x&type=code",
+                &RenderOptions::default()
+            ),
+            "<a href=\"https://github.com/search?q=x&amp;type=code\">GitHub code search: x</a>",
+        );
+    }
+
+    #[test]
+    fn write_html_anchor_uses_unrecognized_urls_as_labels() {
+        assert_eq!(
+            html_anchor("https://example.com/?a=1&b=2", &RenderOptions::default()),
+            "<a href=\"https://example.com/?a=1&amp;b=2\">https://example.com/?a=1&amp;b=2</a>",
+        );
+    }
+
+    #[test]
+    fn write_html_anchor_embeds_images() {
+        let opts = RenderOptions {
+            image_hosts: vec!["i.imgur.com".to_owned()],
+            ..RenderOptions::default()
+        };
+        assert_eq!(
+            html_anchor("https://i.imgur.com/a.png?x=1&y=2", &opts),
+            "<img src=\"https://i.imgur.com/a.png?x=1&amp;y=2\">",
+        );
+    }
+
+    fn recognized(url: &str, opts: &RenderOptions) -> bool {
+        is_recognized_with(&Url::parse(url).unwrap(), opts)
+    }
+
+    #[test]
+    fn is_recognized_with_handled_urls() {
+        let opts = RenderOptions::default();
+        assert!(recognized("https://github.com/gfx-rs/wgpu/pull/42", &opts));
+        assert!(recognized("mailto:someone@example.com", &opts));
+    }
+
+    #[test]
+    fn is_recognized_with_unhandled_urls() {
+        let opts = RenderOptions::default();
+        assert!(!recognized("https://example.com/foo", &opts));
+        // The host is handled, but this path isn't.
+        assert!(!recognized("https://bugzilla.mozilla.org/", &opts));
+        let opts = RenderOptions {
+            disabled_hosts: vec!["github.com".to_owned()],
+            ..RenderOptions::default()
+        };
+        assert!(!recognized("https://github.com/gfx-rs/wgpu/pull/42", &opts));
+    }
+
+    #[test]
+    fn is_recognized_with_link_table_entries() {
+        let opts = RenderOptions {
+            link_table: [("https://example.com/foo".to_owned(), "Foo".to_owned())].into(),
+            ..RenderOptions::default()
+        };
+        assert!(recognized("https://example.com/foo", &opts));
+        assert!(!recognized("https://example.com/bar", &opts));
+    }
+
+    #[test]
+    fn is_recognized_with_image_hosts() {
+        let opts = RenderOptions {
+            image_hosts: vec!["i.imgur.com".to_owned()],
+            ..RenderOptions::default()
+        };
+        assert!(recognized("https://i.imgur.com/a.png", &opts));
+        assert!(!recognized("https://imgur.com/a.png", &opts));
+    }
+}
@@ -1,460 +1,1119 @@
 use std::{
-    fmt::{self, Display},
-    io::{self, stdin},
-    sync::OnceLock,
+    cell::Cell,
+    collections::BTreeMap,
+    env,
+    fmt::{self, Display, Write as _},
+    fs::{self, OpenOptions},
+    io::{self, stdin, Write},
+    num::NonZeroU64,
+    path::{Path, PathBuf},
+    process::{self, Command, Stdio},
+    sync::{Mutex, OnceLock},
+    thread,
+    time::Duration,
 };
 
 use arboard::Clipboard;
-use clap::Parser;
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand, ValueEnum};
 use itertools::Itertools;
-use joinery::JoinableIterator;
 use lazy_format::make_lazy_format;
+use mdlink::{
+    try_write_label, write_html_anchor, CommitSubjectLookup, FancyMarkdownMatched, FileUrlDisplay,
+    IssueTitleLookup, RenderOptions, RenderedLink, RepoPrefix,
+};
 use url::Url;
 
 #[derive(Debug, Parser)]
 #[clap(about, author, version)]
-enum Cli {
+struct Cli {
+    #[clap(subcommand)]
+    source: Source,
+    /// Leave URLs with this host as-is, even if a handler exists for it. May be specified
+    /// multiple times.
+    #[clap(long = "disable-host", value_name = "HOST", global = true)]
+    disabled_hosts: Vec<String>,
+    /// Treat this host as a GitHub Enterprise Server instance, rendering its URLs like
+    /// `github.com`'s. May be specified multiple times.
+    #[clap(long = "github-host", value_name = "HOST", global = true)]
+    github_hosts: Vec<String>,
+    /// Remove well-known tracking query parameters (`utm_*`, `fbclid`, `gclid`, etc.) from
+    /// URLs before rendering them.
+    #[clap(long, global = true)]
+    trim_tracking_params: bool,
+    /// Don't wrap repo names, file paths, symbols, etc. in backticks.
+    #[clap(long, global = true)]
+    no_backticks: bool,
+    /// The separator between a repo, its commit-ish, and a file path in file links (e.g.,
+    /// `" @ "`).
+    #[clap(long, value_name = "SEP", default_value = ":", global = true)]
+    path_separator: String,
+    /// Leave this branch name out of GitHub file, directory, and history links (e.g., `main`).
+    #[clap(long, value_name = "BRANCH", global = true)]
+    hide_default_branch: Option<String>,
+    /// A JSON file mapping GitHub issues and PRs (keyed like `org/repo#42`) to titles to
+    /// append to their labels. Only this local file is consulted, once an issue or PR link is
+    /// rendered; nothing is fetched.
+    #[clap(long, value_name = "PATH", global = true)]
+    title_cache: Option<PathBuf>,
+    /// A JSON file mapping exact URLs to the labels they should be rendered with, overriding
+    /// any handler.
+    #[clap(long, value_name = "PATH", global = true)]
+    shorten: Option<PathBuf>,
+    /// A local `git` checkout of a GitHub repo, used to show the subject lines of linked
+    /// commits. May be specified multiple times.
+    #[clap(
+        long = "local-repo",
+        value_name = "ORG/REPO=PATH",
+        value_parser = parse_local_repo,
+        global = true
+    )]
+    local_repos: Vec<(String, PathBuf)>,
+    /// Sort output lines by their readable label rather than emitting them in input order.
+    #[clap(long, global = true)]
+    sort: bool,
+    /// Emit only the readable label of each link, without Markdown link syntax. URLs that
+    /// aren't recognized are emitted as-is.
+    #[clap(long, global = true, conflicts_with = "format")]
+    labels_only: bool,
+    /// The markup used for rendered links. With `html`, input lines that are already Markdown
+    /// links are converted to anchors too.
+    #[clap(long, value_enum, default_value_t = Format::Markdown, global = true)]
+    format: Format,
+    /// Stop reading input after this many consecutive lines fail to parse as URLs, which usually
+    /// means the wrong input was provided. What was read before then is still output, and
+    /// `mdlink` then exits with an error.
+    #[clap(long, value_name = "N", global = true)]
+    max_line_errors: Option<NonZeroU64>,
+    /// Pass input lines that aren't URLs (including blank lines) through unchanged, rather than
+    /// reporting and dropping them. Lines like `Note: text` aren't URLs; schemes other than
+    /// `http`, `https`, `mailto`, `tel`, and `file` only count when followed by `://`.
+    #[clap(long, global = true)]
+    preserve_non_url_lines: bool,
+    /// Append rendered links to this file instead of printing them to `stdout`. Parent
+    /// directories are created if they don't exist.
+    #[clap(long, value_name = "PATH", global = true)]
+    output_file: Option<PathBuf>,
+    /// Also print rendered links to `stdout` when `--output-file` is specified.
+    #[clap(long, requires = "output_file", global = true)]
+    stdout: bool,
+    /// Also copy all rendered links to the clipboard.
+    #[clap(long, global = true)]
+    copy: bool,
+    /// The GitHub repo that bare `#NUM` inputs refer to, which are expanded into links to its
+    /// issues.
+    #[clap(long, value_name = "ORG/REPO", value_parser = parse_focal_repo, global = true)]
+    focal_repo: Option<String>,
+    /// Append the host of each recognized URL to its label, e.g., `(github.com)`.
+    #[clap(long, global = true)]
+    annotate_host: bool,
+    /// Exit with an error after rendering if any input URL wasn't recognized, listing each of
+    /// them.
+    #[clap(long, global = true)]
+    strict: bool,
+    /// Drop the query from the targets of recognized links, unless it's needed to identify what
+    /// they point to (e.g., GitHub searches).
+    #[clap(long, global = true)]
+    strip_query_on_match: bool,
+    /// Indent each output line by this many spaces, e.g., to nest links in a Markdown list.
+    #[clap(long, value_name = "N", default_value_t = 0, global = true)]
+    indent: usize,
+    /// Embed URLs with this host as images rather than linking to them. May be specified
+    /// multiple times.
+    #[clap(long = "image-host", value_name = "HOST", global = true)]
+    image_hosts: Vec<String>,
+    /// Write version-like tags (e.g., `v1.2.3`) in GitHub file, directory, and history links as
+    /// `@tag`, distinguishing them from branches.
+    #[clap(long, global = true)]
+    mark_tag_refs: bool,
+    /// Leave this GitHub org out of labels for its repos, i.e., `` `repo`#42 `` instead of
+    /// `` `org/repo`#42 ``.
+    #[clap(long, value_name = "ORG", global = true)]
+    home_org: Option<String>,
+    /// How much of the names of GitHub repos is shown in their labels. `--home-org` and `--alias`
+    /// take priority.
+    #[clap(long, value_enum, default_value_t = RepoPrefixArg::Full, global = true)]
+    repo_prefix: RepoPrefixArg,
+    /// How much of the path of `file:` URLs is shown in their labels.
+    #[clap(long, value_enum, default_value_t = FileUrlDisplayArg::Basename, global = true)]
+    file_url_display: FileUrlDisplayArg,
+    /// The label of GitHub issue and PR links. `{prefix}` is replaced with the repo, `{num}` with
+    /// the issue number, and `{url}` with the URL.
+    #[clap(
+        long,
+        value_name = "TEMPLATE",
+        default_value = "{prefix}#{num}",
+        global = true
+    )]
+    issue_format: String,
+    /// Show a repo or project with another name, e.g., `mozilla-central=m-c`. GitHub repos are
+    /// named like `org/repo`. May be specified multiple times.
+    #[clap(
+        long = "alias",
+        value_name = "NAME=DISPLAY",
+        value_parser = parse_alias,
+        global = true
+    )]
+    aliases: Vec<(String, String)>,
+    /// Point MDN links at this locale (e.g., `en-US`) instead of the one in their URL.
+    #[clap(long, value_name = "LOCALE", global = true)]
+    mdn_locale: Option<String>,
+    /// Don't report input lines that fail to parse as URLs. They're still dropped, and still
+    /// count towards `--max-line-errors`.
+    #[clap(long, global = true)]
+    quiet: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+enum Format {
+    /// `[label](url)`
+    Markdown,
+    /// `<a href="url">label</a>`
+    Html,
+}
+
+/// Mirrors [`RepoPrefix`], which the library can't derive [`ValueEnum`] for without depending on
+/// `clap`.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum RepoPrefixArg {
+    /// The org and the repo, i.e., `org/repo`.
+    Full,
+    /// Only the repo, i.e., `repo`.
+    RepoOnly,
+}
+
+impl From<RepoPrefixArg> for RepoPrefix {
+    fn from(arg: RepoPrefixArg) -> Self {
+        match arg {
+            RepoPrefixArg::Full => Self::Full,
+            RepoPrefixArg::RepoOnly => Self::RepoOnly,
+        }
+    }
+}
+
+/// Mirrors [`FileUrlDisplay`], which the library can't derive [`ValueEnum`] for without depending
+/// on `clap`.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum FileUrlDisplayArg {
+    /// Only the file name, i.e., `main.rs`.
+    Basename,
+    /// The whole path, i.e., `/home/user/project/src/main.rs`.
+    FullPath,
+}
+
+impl From<FileUrlDisplayArg> for FileUrlDisplay {
+    fn from(arg: FileUrlDisplayArg) -> Self {
+        match arg {
+            FileUrlDisplayArg::Basename => Self::Basename,
+            FileUrlDisplayArg::FullPath => Self::FullPath,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum Source {
     Clipboard,
     Stdin,
-    Args { urls: Vec<Url> },
+    FromFile {
+        path: PathBuf,
+    },
+    Args {
+        /// URLs, or `#NUM` issue references when `--focal-repo` is specified.
+        #[clap(value_parser = parse_url_arg)]
+        urls: Vec<UrlArg>,
+    },
+    /// Keep watching the clipboard, replacing URLs in new contents with rendered links. Lines that
+    /// aren't URLs are always kept.
+    ///
+    /// `--copy`, `--max-line-errors`, `--output-file`, `--preserve-non-url-lines`, `--sort`, and
+    /// `--strict` can't be used with this.
+    Watch {
+        /// How often to check the clipboard for new contents, in milliseconds.
+        #[clap(long, value_name = "MILLISECONDS", default_value_t = 500)]
+        interval: u64,
+    },
+    /// List the hosts whose URLs get readable labels, and what's recognized for each.
+    Hosts,
+    /// Print cleaned-up URLs, rather than Markdown links.
+    ///
+    /// Tracking parameters and a leading `www.` are removed, and `git` remotes (e.g.,
+    /// `git@github.com:org/repo.git`) are turned into the web pages of their repos.
+    ///
+    /// `--copy`, `--indent`, `--labels-only`, `--output-file`, `--sort`, and `--strict` can't be
+    /// used with this.
+    Normalize {
+        /// URLs, or scp-like `git` remotes.
+        #[clap(value_parser = parse_url_or_git_remote)]
+        urls: Vec<Url>,
+    },
+    /// Set the clipboard to what's read from `stdin`, and keep it set until something else is
+    /// copied. Used internally by `--copy`, which runs this in the background.
+    #[clap(hide = true)]
+    ServeClipboard,
 }
 
 fn main() {
     env_logger::init();
 
+    let Cli {
+        source,
+        disabled_hosts,
+        github_hosts,
+        trim_tracking_params,
+        no_backticks,
+        path_separator,
+        hide_default_branch,
+        title_cache,
+        shorten,
+        local_repos,
+        sort,
+        labels_only,
+        format,
+        max_line_errors,
+        preserve_non_url_lines,
+        output_file,
+        stdout,
+        copy,
+        focal_repo,
+        annotate_host,
+        strict,
+        strip_query_on_match,
+        indent,
+        image_hosts,
+        mark_tag_refs,
+        home_org,
+        repo_prefix,
+        file_url_display,
+        issue_format,
+        aliases,
+        mdn_locale,
+        quiet,
+    } = Cli::parse();
+
+    let opts = &RenderOptions {
+        disabled_hosts,
+        github_hosts,
+        use_backticks: !no_backticks,
+        path_separator: path_separator.into(),
+        hide_default_branch,
+        issue_titles: title_cache.map(|path| {
+            // The cache is only read once an issue or PR link is rendered.
+            let titles = OnceLock::new();
+            IssueTitleLookup(Box::new(move |issue| {
+                titles
+                    .get_or_init(|| read_json_map(&path, "title cache"))
+                    .get(issue)
+                    .cloned()
+            }))
+        }),
+        commit_subjects: (!local_repos.is_empty()).then(|| {
+            let local_repos = local_repos.into_iter().collect::<BTreeMap<_, _>>();
+            // Sorting renders each link twice, so lookups are cached.
+            let cache = Mutex::new(BTreeMap::new());
+            CommitSubjectLookup(Box::new(move |repo, sha| {
+                let path = local_repos.get(repo)?;
+                cache
+                    .lock()
+                    .unwrap()
+                    .entry((repo.to_owned(), sha.to_owned()))
+                    .or_insert_with(|| commit_subject(path, sha))
+                    .clone()
+            }))
+        }),
+        link_table: shorten
+            .map(|path| read_json_map(&path, "link table"))
+            .unwrap_or_default(),
+        annotate_host,
+        strip_query_on_match,
+        image_hosts,
+        mark_tag_refs,
+        home_org,
+        repo_prefix: repo_prefix.into(),
+        file_url_display: file_url_display.into(),
+        issue_format: issue_format.into(),
+        aliases: aliases.into_iter().collect(),
+        mdn_locale,
+    };
+
+    let line_opts = LineOptions {
+        max_line_errors,
+        preserve_non_url_lines,
+        focal_repo: focal_repo.as_deref(),
+        quiet,
+    };
     let buf;
-    let urls: Box<dyn Iterator<Item = Url>> = match Cli::parse() {
-        Cli::Clipboard => {
+    let inputs: Box<dyn Iterator<Item = Result<Input<'_>, TooManyLineErrors>>> = match source {
+        Source::Clipboard => {
             buf = Clipboard::new().unwrap().get_text().unwrap();
-            Box::new(line_iter(&buf))
+            Box::new(line_iter(&buf, line_opts))
         }
-        Cli::Stdin => {
+        Source::Stdin => {
             buf = io::read_to_string(stdin().lock()).expect("failed to read `stdin`");
-            Box::new(line_iter(&buf))
+            Box::new(line_iter(&buf, line_opts))
+        }
+        Source::FromFile { path } => {
+            buf = fs::read_to_string(&path)
+                .unwrap_or_else(|e| panic!("failed to read input file {path:?}: {e}"));
+            Box::new(line_iter(&buf, line_opts))
+        }
+        Source::Args { urls } => {
+            let urls = urls
+                .into_iter()
+                .map(|arg| match arg {
+                    UrlArg::Url(url) => url,
+                    UrlArg::IssueRef(num) => match &focal_repo {
+                        Some(repo) => focal_issue_url(repo, num),
+                        None => Cli::command()
+                            .error(
+                                ErrorKind::MissingRequiredArgument,
+                                format!("`#{num}` can only be expanded with `--focal-repo`"),
+                            )
+                            .exit(),
+                    },
+                })
+                .collect::<Vec<_>>();
+            Box::new(urls.into_iter().map(|url| Ok(Input::Url(url))))
+        }
+        Source::Watch { interval } => {
+            // Rendered links only ever go back to the clipboard, one change at a time.
+            reject_ignored_flags(
+                "watch",
+                &[
+                    ("--copy", copy),
+                    ("--max-line-errors", max_line_errors.is_some()),
+                    ("--output-file", output_file.is_some()),
+                    ("--preserve-non-url-lines", preserve_non_url_lines),
+                    ("--sort", sort),
+                    ("--strict", strict),
+                ],
+            );
+            watch_clipboard(
+                Duration::from_millis(interval),
+                trim_tracking_params,
+                line_opts,
+                |input| render_input(input, opts, labels_only, format, indent).to_string(),
+            )
+        }
+        Source::ServeClipboard => {
+            serve_clipboard();
+            return;
+        }
+        Source::Hosts => {
+            let handlers = mdlink::handlers::builtin();
+            let width = handlers
+                .iter()
+                .map(|handler| handler.hosts().len())
+                .max()
+                .unwrap_or_default();
+            for handler in handlers {
+                println!("{:width$}  {}", handler.hosts(), handler.description());
+            }
+            return;
+        }
+        Source::Normalize { urls } => {
+            // Cleaned-up URLs are printed as-is, one per line.
+            reject_ignored_flags(
+                "normalize",
+                &[
+                    ("--copy", copy),
+                    ("--indent", indent != 0),
+                    ("--labels-only", labels_only),
+                    ("--output-file", output_file.is_some()),
+                    ("--sort", sort),
+                    ("--strict", strict),
+                ],
+            );
+            for mut url in urls {
+                mdlink::trim_tracking_params(&mut url);
+                mdlink::normalize(&mut url);
+                println!("{url}");
+            }
+            return;
         }
-        Cli::Args { urls } => Box::new(urls.into_iter()),
     };
 
-    for url in urls {
-        println!(
-            "{}",
-            make_lazy_format!(|f| {
-                try_write_markdown_url(&url, &mut *f).and_then(|matched| match matched {
-                    FancyMarkdownMatched::No => write!(f, "<{url}>"),
-                    FancyMarkdownMatched::Yes => Ok(()),
-                })
-            })
-        )
-    }
-}
+    let mut output_file = output_file.map(|path| {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap_or_else(|e| {
+                panic!("failed to create parent directories of output file {path:?}: {e}")
+            });
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .unwrap_or_else(|e| panic!("failed to open output file {path:?}: {e}"))
+    });
 
-#[allow(clippy::needless_lifetimes)]
-fn line_iter<'a>(s: &'a str) -> impl Iterator<Item = Url> + 'a {
-    s.lines().zip(1u64..).filter_map(|(line, idx)| {
-        let line = line.trim();
-        if line.is_empty() {
-            return None;
+    // Everything read before too many line errors is still output.
+    let too_many_line_errors = Cell::new(None);
+    let inputs =
+        inputs.map_while(|input| input.map_err(|e| too_many_line_errors.set(Some(e))).ok());
+    let inputs = inputs.map(|mut input| {
+        if let Input::Url(url) = &mut input {
+            if trim_tracking_params {
+                mdlink::trim_tracking_params(url);
+            }
         }
-        match line.parse() {
-            Ok(url) => Some(url),
-            Err(e) => {
-                log::error!("failed to parse line {idx}: {e}. Original line: ({line:?})");
-                None
+        input
+    });
+
+    let inputs: Box<dyn Iterator<Item = Input<'_>>> = if sort {
+        let mut inputs = inputs.collect::<Vec<_>>();
+        inputs.sort_by_cached_key(|input| match input {
+            Input::Url(url) => {
+                let mut label = String::new();
+                match try_write_label(url, opts, &mut label) {
+                    Ok(FancyMarkdownMatched::Yes) => label,
+                    Ok(FancyMarkdownMatched::No) | Err(fmt::Error) => url.to_string(),
+                }
+            }
+            Input::AlreadyRendered { label, .. } => label.to_string(),
+            Input::Verbatim(line) => line.to_string(),
+        });
+        Box::new(inputs.into_iter())
+    } else {
+        Box::new(inputs)
+    };
+
+    let mut copied = copy.then(String::new);
+    let mut unrecognized = Vec::new();
+    for input in inputs {
+        if let Input::Url(url) = &input {
+            if strict && !mdlink::is_recognized_with(url, opts) {
+                unrecognized.push(url.clone());
             }
         }
-    })
+        let rendered = render_input(&input, opts, labels_only, format, indent);
+        if let Some(output_file) = &mut output_file {
+            writeln!(output_file, "{rendered}").expect("failed to write to output file");
+        }
+        if output_file.is_none() || stdout {
+            println!("{rendered}");
+        }
+        if let Some(copied) = &mut copied {
+            writeln!(copied, "{rendered}").unwrap();
+        }
+    }
+
+    if let Some(copied) = copied {
+        copy_to_clipboard(copied);
+    }
+
+    let mut failed = false;
+    if !unrecognized.is_empty() {
+        for url in &unrecognized {
+            log::error!("unrecognized URL: {url}");
+        }
+        log::error!("{} URL(s) weren't recognized", unrecognized.len());
+        failed = true;
+    }
+    if let Some(TooManyLineErrors { consecutive }) = too_many_line_errors.take() {
+        log::error!(
+            "stopped reading input after {consecutive} consecutive lines failed to parse; is this \
+            the input you meant to provide?"
+        );
+        failed = true;
+    }
+    if failed {
+        process::exit(1);
+    }
 }
 
-#[derive(Clone, Copy, Debug)]
-enum FancyMarkdownMatched {
-    Yes,
-    No,
+/// Exits with an error naming the first of `flags` that was specified, since `subcommand` would
+/// ignore it. Each flag is paired with whether it was specified.
+fn reject_ignored_flags(subcommand: &str, flags: &[(&str, bool)]) {
+    if let Some((flag, _)) = flags.iter().find(|(_, specified)| *specified) {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                format!("`{flag}` can't be used with `{subcommand}`"),
+            )
+            .exit()
+    }
 }
 
-fn try_write_markdown_url(
-    url: &Url,
-    mut f: impl fmt::Write,
-) -> Result<FancyMarkdownMatched, fmt::Error> {
-    if let "http" | "https" = url.scheme() {
-        if let Some(host) = url.host_str() {
-            let mut path_segments = url
-                .path_segments()
-                .expect("got URL with host but no path segments iterator (!?)");
-            match host {
-                "github.com" => {
-                    if let Some((org, repo)) = path_segments.next_tuple() {
-                        if path_segments.clone().next().is_none() {
-                            write!(f, "[`{org}/{repo}`]({url})")?;
-                            return Ok(FancyMarkdownMatched::Yes);
-                        }
-                        if let Some(("issues" | "pull", issue_num)) =
-                            path_segments.clone().next_tuple()
-                        {
-                            write!(f, "[`{org}/{repo}`#{issue_num}]({url})")?;
-                            return Ok(FancyMarkdownMatched::Yes);
-                        }
+/// Copies `text` to the clipboard, where it stays after `mdlink` exits.
+///
+/// On Linux, the X11 and Wayland clipboards are emptied when the process that set them exits
+/// (unless a clipboard manager takes over), so `mdlink serve-clipboard` is spawned in the
+/// background to own the clipboard until something else is copied.
+// The daemon isn't waited on because it's meant to outlive `mdlink`, which exits right after
+// this. It's then reparented to `init`, which reaps it when it exits.
+#[allow(clippy::zombie_processes)]
+fn copy_to_clipboard(text: String) {
+    if cfg!(target_os = "linux") {
+        // Errors are reported from here, since the daemon's output goes nowhere.
+        Clipboard::new().expect("failed to access the clipboard");
+        let exe = env::current_exe().expect("failed to find the `mdlink` executable");
+        let mut daemon = Command::new(exe)
+            .arg("serve-clipboard")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .current_dir("/")
+            .spawn()
+            .expect("failed to spawn a process to own the clipboard");
+        daemon
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(text.as_bytes())
+            .expect("failed to pass rendered links to the clipboard process");
+    } else {
+        Clipboard::new()
+            .unwrap()
+            .set_text(text)
+            .expect("failed to copy rendered links to the clipboard");
+    }
+}
 
-                        {
-                            let mut path_segments = path_segments.clone();
-                            match path_segments.next_tuple() {
-                                Some(("blob", commitish)) => {
-                                    enum LineNumberSpec<'a> {
-                                        Single(&'a str),
-                                        Range { start: &'a str, end: &'a str },
-                                    }
-                                    let file_path_segments = path_segments;
-                                    let line_num_spec = url.fragment().and_then(|frag| {
-                                        static LINE_NUM_SPEC_RE: OnceLock<regex::Regex> =
-                                            OnceLock::new();
-                                        let line_num_spec_re = LINE_NUM_SPEC_RE.get_or_init(|| {
-                                            regex::Regex::new(concat!(
-                                                r#"L(?P<start>\d+)"#,
-                                                r#"(:?-L(?P<end>\d+))?"#,
-                                            ))
-                                            .unwrap()
-                                        });
-                                        line_num_spec_re.captures(frag).map(|caps| {
-                                            let start = caps
-                                                .name("start")
-                                                .map(|m| m.as_str())
-                                                .expect(concat!(
-                                                    "matched line number spec. regex, ",
-                                                    "but unconditional `start` capture not found"
-                                                ));
-
-                                            caps.name("end")
-                                                .map(|m| m.as_str())
-                                                .map(|end| LineNumberSpec::Range { start, end })
-                                                .unwrap_or(LineNumberSpec::Single(start))
-                                        })
-                                    });
-                                    write!(
-                                        f,
-                                        "[`{org}/{repo}`:`{commitish}`:`{}`{}]({url})",
-                                        file_path_segments.join_with('/'),
-                                        make_lazy_format!(|f| {
-                                            match line_num_spec {
-                                                Some(LineNumberSpec::Single(num)) => {
-                                                    write!(f, ":{num}")
-                                                }
-                                                Some(LineNumberSpec::Range { start, end }) => {
-                                                    write!(f, ":{start}-{end}")
-                                                }
-                                                None => Ok(()),
-                                            }
-                                        })
-                                    )?;
-                                    return Ok(FancyMarkdownMatched::Yes);
-                                }
-                                Some(("commit", commitish)) => {
-                                    if path_segments.clone().next().is_none() {
-                                        write!(f, "[`{org}/{repo}`:`{commitish}`]({url})")?;
-                                        return Ok(FancyMarkdownMatched::Yes);
-                                    }
-
-                                    let file_path_segments = path_segments;
-                                    write!(
-                                        f,
-                                        "[`{org}/{repo}`:`{commitish}`:`{}`]({url})",
-                                        file_path_segments.join_with('/'),
-                                    )?;
-                                    return Ok(FancyMarkdownMatched::Yes);
-                                }
-                                Some(("releases", "tag")) => {
-                                    if let Some(tag) = path_segments.next() {
-                                        match (path_segments.next(), path_segments.next()) {
-                                            (Some(""), None) | (None, ..) => {
-                                                static COMPONENT_VERSION_RE: OnceLock<
-                                                    regex::Regex,
-                                                > = OnceLock::new();
-
-                                                if let Some(captures) = COMPONENT_VERSION_RE
-                                                    .get_or_init(|| {
-                                                        regex::Regex::new(concat!(
-                                                            r"(?P<component>.+)",
-                                                            "-",
-                                                            r"(?P<version>v\d+(:?\.\d+){0,2})"
-                                                        ))
-                                                        .unwrap()
-                                                    })
-                                                    .captures(tag)
-                                                {
-                                                    let component = &captures["component"];
-                                                    let version = &captures["version"];
-                                                    write!(f, "[`{component}` {version}]({url})")?;
-                                                } else {
-                                                    write!(f, "[`{tag}` tag release]({url})")?;
-                                                }
-                                                return Ok(FancyMarkdownMatched::Yes);
-                                            }
-                                            _ => (),
-                                        }
-                                    }
-                                }
-                                _ => (),
-                            }
-                        }
-                    }
-                }
-                "bugzil.la" => {
-                    if let Some((bug_id,)) = path_segments.collect_tuple() {
-                        render_bugzilla(url, bug_id, f)?;
-                        return Ok(FancyMarkdownMatched::Yes);
-                    }
-                }
-                "bugzilla.mozilla.org" => {
-                    if let Some("show_bug.cgi") = path_segments.next() {
-                        if path_segments.next().is_none() {
-                            if let Some(bug_id) = url
-                                .query_pairs()
-                                .find_map(|(k, v)| (k == "id").then_some(v))
-                            {
-                                render_bugzilla(url, bug_id.as_ref(), f)?;
-                                return Ok(FancyMarkdownMatched::Yes);
-                            }
-                        }
-                    }
-                }
-                "phabricator.services.mozilla.com" => {
-                    if let Some(("differential", "diff", diff_id)) =
-                        path_segments.clone().next_tuple()
-                    {
-                        if let Some(("",)) = path_segments.collect_tuple() {
-                            // extra slash at end, ignore it
-                        }
-                        write!(f, "[diff {diff_id}]({url})")?;
-                        return Ok(FancyMarkdownMatched::Yes);
-                    } else if let Some((id,)) = path_segments.collect_tuple() {
-                        if id
-                            .strip_prefix('D')
-                            .is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit()))
-                        {
-                            write!(f, "[{id}]({url})")?;
-                            return Ok(FancyMarkdownMatched::Yes);
-                        }
-                    }
-                }
-                "crates.io" => {
-                    if let Some(("crates", crate_name, crate_version)) =
-                        path_segments.collect_tuple()
-                    {
-                        write!(f, "[`{crate_name}` v{crate_version}]({url})")?;
-                        return Ok(FancyMarkdownMatched::Yes);
-                    }
-                }
-                "docs.rs" => {
-                    if let Some((crate_pkg_name, ver, crate_module_name)) =
-                        path_segments.next_tuple()
-                    {
-                        if crate_pkg_name.replace('-', "_") == crate_module_name {
-                            log::debug!("ignoring version {ver:?}");
-
-                            let mut symbol_caps = None;
-                            let mut fragment_caps = None;
-                            match extract_rust_symbol_path(
-                                crate_module_name,
-                                path_segments,
-                                url.fragment(),
-                                &mut symbol_caps,
-                                &mut fragment_caps,
-                            ) {
-                                Some(module_path) => {
-                                    write!(f, "[`{module_path}`]({url})")?;
-                                    return Ok(FancyMarkdownMatched::Yes);
-                                }
-                                None => return Ok(FancyMarkdownMatched::No),
-                            };
-                        }
-                    }
-                }
-                "doc.rust-lang.org" => {
-                    if let Some(
-                        (
-                            "stable" | "beta" | "nightly",
-                            crate_module_name @ ("core" | "alloc" | "std"),
-                        )
-                        | (crate_module_name @ ("core" | "alloc" | "std"), _),
-                    ) = path_segments.next_tuple()
-                    {
-                        let mut symbol_caps = None;
-                        let mut fragment_caps = None;
-                        match extract_rust_symbol_path(
-                            crate_module_name,
-                            path_segments,
-                            url.fragment(),
-                            &mut symbol_caps,
-                            &mut fragment_caps,
-                        ) {
-                            Some(module_path) => {
-                                write!(f, "[`{module_path}`]({url})")?;
-                                return Ok(FancyMarkdownMatched::Yes);
-                            }
-                            None => return Ok(FancyMarkdownMatched::No),
-                        };
-                    }
-                }
-                "rust-lang.github.io" => {
-                    if let Some(("rust-clippy", release_stage, "index.html")) =
-                        path_segments.collect_tuple()
-                    {
-                        if matches!(release_stage, "stable" | "beta" | "nightly") {
-                            // NOTE: This may be a valid lint name, and we hope so, but we can't
-                            // readily distinguish.
-                            if let Some(search_term) =
-                                url.fragment().and_then(|f| f.strip_prefix('/'))
-                            {
-                                write!(f, "[`clippy::{search_term}` in `{release_stage}`]({url})")?;
-                            } else {
-                                write!(f, "[`clippy` lints in `{release_stage}`]({url})")?;
-                            }
-                            return Ok(FancyMarkdownMatched::Yes);
-                        }
-                    }
-                }
-                "searchfox.org" => {
-                    let is_moz_central = path_segments
-                        .next()
-                        .filter(|repo| repo == &"mozilla-central")
-                        .and_then(|_repo| path_segments.next())
-                        .is_some_and(|history| match history {
-                            "source" => true,
-                            "rev" => {
-                                let _rev_hash = path_segments.next();
-                                true
-                            }
-                            _ => false,
-                        });
-                    if is_moz_central {
-                        let file_path = path_segments.join_with('/');
-                        let line_range_probably = make_lazy_format!(|f| {
-                            if let Some(fragment) = url.fragment() {
-                                write!(f, "{fragment}")?;
-                            }
-                            Ok(())
-                        });
-                        write!(f, "[`{file_path}`:{line_range_probably}]({url})")?;
-                        return Ok(FancyMarkdownMatched::Yes);
-                    }
-                }
-                "treeherder.mozilla.org" => {
-                    if let Some(("jobs",)) = path_segments.collect_tuple() {
-                        let mut repo = None;
-                        let mut revision = None;
-                        for (key, value) in url.query_pairs() {
-                            match key.as_ref() {
-                                "repo" => repo = repo.or(Some(value)),
-                                "revision" => revision = revision.or(Some(value)),
-                                _ => (),
-                            }
-                        }
+/// Sets the clipboard to what's read from `stdin`, then keeps serving it until something else is
+/// copied. See [`copy_to_clipboard`].
+fn serve_clipboard() {
+    let Ok(text) = io::read_to_string(stdin().lock()) else {
+        return;
+    };
+    let Ok(mut clipboard) = Clipboard::new() else {
+        return;
+    };
+    // Nobody is around to hear about errors by now.
+    #[cfg(target_os = "linux")]
+    let _ = arboard::SetExtLinux::wait(clipboard.set()).text(text);
+    #[cfg(not(target_os = "linux"))]
+    let _ = clipboard.set_text(text);
+}
 
-                        if let (Some(repo), Some(revision)) = (repo, revision) {
-                            let revision = revision.get(..12).unwrap_or(revision.as_ref());
-                            write!(f, "[`{repo}:{revision}`]({url})")?;
-                            return Ok(FancyMarkdownMatched::Yes);
-                        }
+fn render_input<'a>(
+    input: &'a Input<'_>,
+    opts: &'a RenderOptions,
+    labels_only: bool,
+    format: Format,
+    indent: usize,
+) -> impl Display + 'a {
+    make_lazy_format!(|f| {
+        write!(f, "{:indent$}", "")?;
+        match input {
+            Input::Url(url) => {
+                if labels_only {
+                    try_write_label(url, opts, &mut *f).and_then(|matched| match matched {
+                        FancyMarkdownMatched::No => write!(f, "{url}"),
+                        FancyMarkdownMatched::Yes => Ok(()),
+                    })
+                } else {
+                    match format {
+                        Format::Markdown => write!(f, "{}", RenderedLink::new(url, opts)),
+                        Format::Html => write_html_anchor(url, opts, &mut *f),
                     }
                 }
-                "gpuweb.github.io" => {
-                    if let Some(("cts", "standalone", "")) = path_segments.collect_tuple() {
-                        if let Some(test_path) =
-                            url.query_pairs().find_map(|(k, v)| (k == "q").then_some(v))
-                        {
-                            write!(f, "[`{test_path}`]({url})")?;
-                            return Ok(FancyMarkdownMatched::Yes);
-                        }
-                    }
+            }
+            Input::AlreadyRendered { label, .. } if labels_only => write!(f, "{label}"),
+            Input::AlreadyRendered { url, .. } if format == Format::Html => {
+                write_html_anchor(url, opts, &mut *f)
+            }
+            Input::AlreadyRendered { line, .. } | Input::Verbatim(line) => write!(f, "{line}"),
+        }
+    })
+}
+
+/// Polls the clipboard every `interval`, replacing any URLs in new contents with rendered
+/// links. Lines that aren't URLs are kept as-is. Already-rendered links are passed through, so
+/// `mdlink`'s own output is never re-processed.
+fn watch_clipboard(
+    interval: Duration,
+    trim_tracking_params: bool,
+    line_opts: LineOptions<'_>,
+    render: impl Fn(&Input<'_>) -> String,
+) -> ! {
+    let mut clipboard = Clipboard::new().unwrap();
+    // Only changes made after we start watching are processed.
+    let mut last_seen = clipboard.get_text().ok();
+    loop {
+        thread::sleep(interval);
+        let Ok(text) = clipboard.get_text() else {
+            continue;
+        };
+        if last_seen.as_ref() == Some(&text) {
+            continue;
+        }
+
+        if let Some(rendered) =
+            render_clipboard_text(&text, trim_tracking_params, line_opts, &render)
+        {
+            log::info!("rendered new clipboard contents");
+            match clipboard.set_text(&rendered) {
+                Ok(()) => {
+                    last_seen = Some(rendered);
+                    continue;
                 }
-                _ => (),
+                Err(e) => log::error!("failed to write rendered links to the clipboard: {e}"),
             }
         }
+        last_seen = Some(text);
     }
-
-    Ok(FancyMarkdownMatched::No)
 }
 
-fn render_bugzilla(url: &Url, bug_id: &str, mut f: impl fmt::Write) -> fmt::Result {
-    let (prefix, postfix) = if bug_id.chars().all(|c| c.is_ascii_digit()) {
-        ("bug ", "")
-    } else {
-        ("`", "`")
+/// Renders the URLs in clipboard `text` for [`watch_clipboard`]. Everything else, including line
+/// endings, is kept as-is. `None` is returned if there are no web URLs to render.
+fn render_clipboard_text(
+    text: &str,
+    trim_tracking_params: bool,
+    line_opts: LineOptions<'_>,
+    render: impl Fn(&Input<'_>) -> String,
+) -> Option<String> {
+    // Everything that isn't a URL is kept, so there's never a line error to stop at.
+    let line_opts = LineOptions {
+        max_line_errors: None,
+        preserve_non_url_lines: true,
+        ..line_opts
     };
+    let mut inputs = line_iter(text, line_opts)
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+    let has_web_url = inputs
+        .iter()
+        .any(|input| matches!(input, Input::Url(url) if matches!(url.scheme(), "http" | "https")));
+    if !has_web_url {
+        return None;
+    }
+    if trim_tracking_params {
+        for input in &mut inputs {
+            if let Input::Url(url) = input {
+                mdlink::trim_tracking_params(url);
+            }
+        }
+    }
+    // There's one input per line, which doesn't include its line ending.
+    let line_endings = text.split_inclusive('\n').map(|line| {
+        let content = line
+            .strip_suffix('\n')
+            .map_or(line, |line| line.strip_suffix('\r').unwrap_or(line));
+        &line[content.len()..]
+    });
+    Some(
+        inputs
+            .iter()
+            .zip(line_endings)
+            .map(|(input, line_ending)| render(input) + line_ending)
+            .collect(),
+    )
+}
+
+fn parse_focal_repo(arg: &str) -> Result<String, String> {
+    match arg.split('/').collect_tuple() {
+        Some((org, repo)) if !org.is_empty() && !repo.is_empty() => Ok(arg.to_owned()),
+        _ => Err(format!("expected `ORG/REPO`, got {arg:?}")),
+    }
+}
+
+#[derive(Clone, Debug)]
+enum UrlArg {
+    Url(Url),
+    /// A bare `#NUM` reference to an issue or PR in the `--focal-repo`.
+    IssueRef(u64),
+}
 
-    let comment;
-    let mut comment_display: &dyn Display = &"";
+fn parse_url_or_git_remote(arg: &str) -> Result<Url, url::ParseError> {
+    match mdlink::parse_scp_like_remote(arg) {
+        Some(url) => Ok(url),
+        None => arg.parse(),
+    }
+}
+
+fn parse_url_arg(arg: &str) -> Result<UrlArg, url::ParseError> {
+    match issue_ref_number(arg) {
+        Some(num) => Ok(UrlArg::IssueRef(num)),
+        None => arg.parse().map(UrlArg::Url),
+    }
+}
+
+/// Parses `s` as a bare issue reference, i.e., `#42`.
+fn issue_ref_number(s: &str) -> Option<u64> {
+    s.strip_prefix('#')
+        .filter(|num| !num.is_empty() && num.bytes().all(|b| b.is_ascii_digit()))?
+        .parse()
+        .ok()
+}
+
+/// The URL of issue `num` in `repo`. GitHub redirects it to the PR if that's what `num` is.
+fn focal_issue_url(repo: &str, num: u64) -> Url {
+    format!("https://github.com/{repo}/issues/{num}")
+        .parse()
+        .unwrap()
+}
+
+/// Looks up the subject line of `commitish` in the local `git` checkout at `repo_path`. Any
+/// failure (e.g., `git` isn't installed, or the commit isn't known locally) yields `None`.
+fn commit_subject(repo_path: &Path, commitish: &str) -> Option<String> {
+    // Only SHAs are looked up, which also keeps arbitrary URL text from being passed to `git` as
+    // an option.
+    if commitish.is_empty() || !commitish.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(["log", "-1", "--format=%s", commitish, "--"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let subject = String::from_utf8(output.stdout).ok()?;
+    let subject = subject.trim();
+    (output.status.success() && !subject.is_empty()).then(|| subject.to_owned())
+}
 
-    if let Some(fragment) = url.fragment() {
-        if let Some(("", comment_id)) = fragment.split_once('c') {
-            comment = make_lazy_format!(|f| write!(f, ", comment {comment_id}"));
-            comment_display = &comment;
+/// Reads a JSON object of strings from `path`, which is described as `what` in errors.
+fn read_json_map(path: &Path, what: &str) -> BTreeMap<String, String> {
+    let contents =
+        fs::read_to_string(path).unwrap_or_else(|e| panic!("failed to read {what} {path:?}: {e}"));
+    serde_json::from_str(&contents)
+        .unwrap_or_else(|e| panic!("failed to parse {what} {path:?}: {e}"))
+}
+
+fn parse_local_repo(arg: &str) -> Result<(String, PathBuf), String> {
+    let (repo, path) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected `ORG/REPO=PATH`, got {arg:?}"))?;
+    Ok((repo.to_owned(), path.into()))
+}
+
+fn parse_alias(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((name, display)) if !name.is_empty() && !display.is_empty() => {
+            Ok((name.to_owned(), display.to_owned()))
         }
+        _ => Err(format!("expected `NAME=DISPLAY`, got {arg:?}")),
     }
-    write!(f, "[{prefix}{bug_id}{postfix}{comment_display}]({url})")
 }
 
-fn extract_rust_symbol_path<'a>(
-    crate_module_name: &'a str,
-    mut path_segments: impl Clone + DoubleEndedIterator<Item = &'a str> + 'a,
-    fragment: Option<&'a str>,
-    symbol_caps: &'a mut Option<regex::Captures<'a>>,
-    fragment_caps: &'a mut Option<regex::Captures<'a>>,
-) -> Option<impl Clone + Display + 'a> {
-    let symbol = match path_segments.next_back() {
-        Some("index.html" | "") | None => None,
-        Some(symbol) => {
-            static SYMBOL_RE: OnceLock<regex::Regex> = OnceLock::new();
-            let symbol_re = SYMBOL_RE.get_or_init(|| {
-                regex::Regex::new(concat!(
-                    "(?P<symbol_kind>constant|struct|fn|enum|trait|attr|primitive|type)",
-                    r"\.",
-                    r"(?P<ident>\w+)",
-                    r"\.html"
-                ))
-                .unwrap()
-            });
-            *symbol_caps = symbol_re.captures(symbol);
-            match symbol_caps
-                .as_ref()
-                .map(|caps| (&caps["symbol_kind"], &caps["ident"]))
-            {
-                None => return None,
-                some => some,
+#[derive(Debug, PartialEq)]
+enum Input<'a> {
+    Url(Url),
+    /// A line that's already a Markdown link, as `mdlink` would emit it. It's passed through
+    /// as-is, so running `mdlink` over its own output is a no-op, unless it's converted to
+    /// another [`Format`].
+    AlreadyRendered {
+        line: &'a str,
+        label: &'a str,
+        url: Url,
+    },
+    /// A line that isn't a URL, passed through unchanged with `--preserve-non-url-lines`.
+    Verbatim(&'a str),
+}
+
+/// Yielded by [`line_iter`] once `--max-line-errors` consecutive lines have failed to parse.
+/// Input should be read no further.
+#[derive(Debug, PartialEq)]
+struct TooManyLineErrors {
+    consecutive: u64,
+}
+
+/// How [`line_iter`] treats input lines; see the `Cli` fields of the same names.
+#[derive(Clone, Copy, Debug)]
+struct LineOptions<'a> {
+    max_line_errors: Option<NonZeroU64>,
+    preserve_non_url_lines: bool,
+    focal_repo: Option<&'a str>,
+    quiet: bool,
+}
+
+fn line_iter<'a>(
+    s: &'a str,
+    opts: LineOptions<'a>,
+) -> impl Iterator<Item = Result<Input<'a>, TooManyLineErrors>> + 'a {
+    let LineOptions {
+        max_line_errors,
+        preserve_non_url_lines,
+        focal_repo,
+        quiet,
+    } = opts;
+    let mut consecutive_errors = 0;
+    s.lines()
+        .zip(1u64..)
+        .filter_map(move |(original_line, idx)| {
+            let line = original_line.trim();
+            if line.is_empty() {
+                return preserve_non_url_lines.then_some(Ok(Input::Verbatim(original_line)));
             }
-        }
+            let input = if let Some((label, url)) = parse_markdown_link(line) {
+                Input::AlreadyRendered { line, label, url }
+            } else {
+                let expanded = focal_repo
+                    .zip(issue_ref_number(line))
+                    .map(|(repo, num)| focal_issue_url(repo, num));
+                match expanded.map_or_else(|| line.parse(), Ok) {
+                    Ok(url) if preserve_non_url_lines && !looks_like_url(line, &url) => {
+                        Input::Verbatim(original_line)
+                    }
+                    Ok(url) => Input::Url(url),
+                    Err(_) if preserve_non_url_lines => Input::Verbatim(original_line),
+                    Err(e) => {
+                        if !quiet {
+                            log::error!(
+                                "failed to parse line {idx}: {e}. Original line: ({line:?})"
+                            );
+                        }
+                        consecutive_errors += 1;
+                        return max_line_errors
+                            .is_some_and(|max| consecutive_errors >= max.get())
+                            .then_some(Err(TooManyLineErrors {
+                                consecutive: consecutive_errors,
+                            }));
+                    }
+                }
+            };
+            // Any line that's output, not just URLs, breaks a run of errors.
+            consecutive_errors = 0;
+            Some(Ok(input))
+        })
+}
+
+/// Whether `line`, which parsed as `url`, is meant to be a URL. Prose like `Note: remember this`
+/// parses as a URL with a made-up scheme, so other schemes only count with a `://`.
+fn looks_like_url(line: &str, url: &Url) -> bool {
+    matches!(url.scheme(), "http" | "https" | "mailto" | "tel" | "file") || line.contains("://")
+}
+
+/// Returns the label and URL of `line` if it's a Markdown link (`[label](url)`) or autolink
+/// (`<url>`). An autolink's label is its URL.
+fn parse_markdown_link(line: &str) -> Option<(&str, Url)> {
+    let (label, url) = if let Some(rest) = line.strip_prefix('[') {
+        rest.strip_suffix(')')?.rsplit_once("](")?
+    } else {
+        let url = line.strip_prefix('<')?.strip_suffix('>')?;
+        (url, url)
     };
+    Url::parse(url).ok().map(|url| (label, url))
+}
 
-    let fragment = match fragment {
-        None => None,
-        Some(fragment) => {
-            static FRAGMENT_CAPS: OnceLock<regex::Regex> = OnceLock::new();
-            let fragment_re = FRAGMENT_CAPS.get_or_init(|| {
-                regex::Regex::new(concat!(
-                    "(tymethod|method|associatedconstant|structfield)",
-                    r"\.",
-                    r"(?P<ident>\w+)"
-                ))
-                .unwrap()
-            });
-            *fragment_caps = fragment_re.captures(fragment);
-            fragment_caps.as_ref().map(|caps| &caps["ident"])
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE_OPTS: LineOptions<'static> = LineOptions {
+        max_line_errors: None,
+        preserve_non_url_lines: false,
+        focal_repo: None,
+        quiet: true,
     };
 
-    let mut symbol_name = None;
-    let mut crate_module_name = Some(crate_module_name);
-    if let Some((kind, name)) = symbol {
-        symbol_name = Some(name);
-        if kind == "primitive" {
-            crate_module_name = None
-        }
+    fn lines<'a>(s: &'a str, opts: LineOptions<'a>) -> Vec<Result<Input<'a>, TooManyLineErrors>> {
+        line_iter(s, opts).collect()
     }
 
-    Some(
-        crate_module_name
-            .into_iter()
-            .chain(path_segments)
-            .chain(symbol_name)
-            .chain(fragment)
-            .join_with("::"),
-    )
+    fn url(s: &str) -> Result<Input<'static>, TooManyLineErrors> {
+        Ok(Input::Url(s.parse().unwrap()))
+    }
+
+    #[test]
+    fn line_iter_preserves_prose_that_parses_as_a_url() {
+        let opts = LineOptions {
+            preserve_non_url_lines: true,
+            ..LINE_OPTS
+        };
+        assert_eq!(
+            lines(
+                "Note: remember this\nhttps://example.com/\n\n  not a URL\nmailto:a@b.c\nx-custom://thing",
+                opts
+            ),
+            [
+                Ok(Input::Verbatim("Note: remember this")),
+                url("https://example.com/"),
+                Ok(Input::Verbatim("")),
+                Ok(Input::Verbatim("  not a URL")),
+                url("mailto:a@b.c"),
+                url("x-custom://thing"),
+            ]
+        );
+    }
+
+    #[test]
+    fn line_iter_passes_rendered_links_through() {
+        assert_eq!(
+            lines(
+                "[`a/b`#1](https://github.com/a/b/pull/1)\n  <https://example.com/>  ",
+                LINE_OPTS
+            ),
+            [
+                Ok(Input::AlreadyRendered {
+                    line: "[`a/b`#1](https://github.com/a/b/pull/1)",
+                    label: "`a/b`#1",
+                    url: "https://github.com/a/b/pull/1".parse().unwrap(),
+                }),
+                Ok(Input::AlreadyRendered {
+                    line: "<https://example.com/>",
+                    label: "https://example.com/",
+                    url: "https://example.com/".parse().unwrap(),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn line_iter_stops_after_consecutive_line_errors() {
+        let opts = LineOptions {
+            max_line_errors: NonZeroU64::new(2),
+            ..LINE_OPTS
+        };
+        // URLs reset the count, so only the last two errors are consecutive.
+        assert_eq!(
+            lines(
+                "oops\nhttps://example.com/\noops\nhttps://example.org/\noops\noops",
+                opts
+            ),
+            [
+                url("https://example.com/"),
+                url("https://example.org/"),
+                Err(TooManyLineErrors { consecutive: 2 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn line_iter_resets_line_errors_on_any_output_line() {
+        let opts = LineOptions {
+            max_line_errors: NonZeroU64::new(2),
+            ..LINE_OPTS
+        };
+        assert_eq!(
+            lines(
+                "oops
+<https://example.com/>
+oops
+[a](https://example.org/)",
+                opts
+            ),
+            [
+                Ok(Input::AlreadyRendered {
+                    line: "<https://example.com/>",
+                    label: "https://example.com/",
+                    url: "https://example.com/".parse().unwrap(),
+                }),
+                Ok(Input::AlreadyRendered {
+                    line: "[a](https://example.org/)",
+                    label: "a",
+                    url: "https://example.org/".parse().unwrap(),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn max_line_errors_must_be_positive() {
+        assert!(Cli::try_parse_from(["mdlink", "--max-line-errors", "0", "args"]).is_err());
+        assert!(Cli::try_parse_from(["mdlink", "--max-line-errors", "1", "args"]).is_ok());
+    }
+
+    #[test]
+    fn line_iter_expands_issue_refs_with_a_focal_repo() {
+        let opts = LineOptions {
+            focal_repo: Some("gfx-rs/wgpu"),
+            ..LINE_OPTS
+        };
+        assert_eq!(
+            lines("#42\n#x", opts),
+            [url("https://github.com/gfx-rs/wgpu/issues/42")]
+        );
+        assert_eq!(lines("#42", LINE_OPTS), []);
+    }
+
+    #[test]
+    fn render_clipboard_text_keeps_line_endings() {
+        let render = |input: &Input<'_>| match input {
+            Input::Url(url) => format!("<{url}>"),
+            Input::AlreadyRendered { line, .. } | Input::Verbatim(line) => line.to_string(),
+        };
+        assert_eq!(
+            render_clipboard_text(
+                "a\r\nhttps://example.com/\r\n\nb\n",
+                false,
+                LINE_OPTS,
+                render
+            )
+            .as_deref(),
+            Some("a\r\n<https://example.com/>\r\n\nb\n")
+        );
+        assert_eq!(
+            render_clipboard_text("https://example.com/", false, LINE_OPTS, render).as_deref(),
+            Some("<https://example.com/>")
+        );
+        assert_eq!(
+            render_clipboard_text("no URLs\r\nmailto:a@b.c", false, LINE_OPTS, render),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_markdown_link_of_links_and_autolinks() {
+        let example = "https://example.com/".parse::<Url>().unwrap();
+        assert_eq!(
+            parse_markdown_link("[a [b] c](https://example.com/)"),
+            Some(("a [b] c", example.clone()))
+        );
+        assert_eq!(
+            parse_markdown_link("<https://example.com/>"),
+            Some(("https://example.com/", example))
+        );
+        assert_eq!(parse_markdown_link("[a](not a url)"), None);
+        assert_eq!(parse_markdown_link("<not a url>"), None);
+        assert_eq!(parse_markdown_link("https://example.com/"), None);
+    }
+
+    #[test]
+    fn render_input_converts_rendered_links_to_html() {
+        let input = lines("[`a/b`#1](https://github.com/a/b/pull/1)", LINE_OPTS)
+            .remove(0)
+            .unwrap();
+        let render =
+            |format| render_input(&input, &RenderOptions::default(), false, format, 0).to_string();
+        assert_eq!(
+            render(Format::Html),
+            r#"<a href="https://github.com/a/b/pull/1">`a/b`#1</a>"#
+        );
+        assert_eq!(
+            render(Format::Markdown),
+            "[`a/b`#1](https://github.com/a/b/pull/1)"
+        );
+    }
+
+    #[test]
+    fn render_input_writes_labels_of_rendered_links() {
+        let inputs = lines(
+            "[`a/b`#1](https://github.com/a/b/pull/1)\n<https://example.com/>",
+            LINE_OPTS,
+        );
+        let labels = inputs
+            .iter()
+            .map(|input| {
+                let input = input.as_ref().unwrap();
+                render_input(input, &RenderOptions::default(), true, Format::Markdown, 0)
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(labels, ["`a/b`#1", "https://example.com/"]);
+    }
+
+    #[test]
+    fn serve_clipboard_is_a_hidden_subcommand() {
+        let cli = Cli::try_parse_from(["mdlink", "serve-clipboard"]).unwrap();
+        assert!(matches!(cli.source, Source::ServeClipboard));
+        let help = Cli::command().render_help().to_string();
+        assert!(!help.contains("serve-clipboard"));
+    }
+
+    #[test]
+    fn labels_only_conflicts_with_format() {
+        assert!(
+            Cli::try_parse_from(["mdlink", "--labels-only", "--format", "html", "args"]).is_err()
+        );
+    }
+
+    #[test]
+    fn issue_ref_number_of_bare_refs() {
+        assert_eq!(issue_ref_number("#42"), Some(42));
+        assert_eq!(issue_ref_number("#"), None);
+        assert_eq!(issue_ref_number("#4a"), None);
+        assert_eq!(issue_ref_number("#-1"), None);
+        assert_eq!(issue_ref_number("42"), None);
+    }
 }
@@ -0,0 +1,148 @@
+use mdlink::{
+    CommitSubjectLookup, FileUrlDisplay, IssueTitleLookup, RenderOptions, RenderedLink, RepoPrefix,
+};
+use url::Url;
+
+/// Renders every URL in `golden.txt` and checks it against the rendering that follows it.
+/// Mismatches are collected so that a single run reports all of them.
+///
+/// Each `# ` heading starts a section rendered with the default [`RenderOptions`], which
+/// `#! option` and `#! option = value` lines change for the rest of the section.
+#[test]
+fn golden() {
+    let mut opts = RenderOptions::default();
+    let mut pending = None;
+    let mut failures = Vec::new();
+    for (line, line_num) in include_str!("golden.txt").lines().zip(1..) {
+        if let Some(option) = line.strip_prefix("#!") {
+            assert!(
+                pending.is_none(),
+                "option on line {line_num} comes between a URL and its expected rendering"
+            );
+            set_option(&mut opts, option.trim())
+                .unwrap_or_else(|e| panic!("bad option on line {line_num}: {e}"));
+            continue;
+        }
+        if line.starts_with("# ") {
+            opts = RenderOptions::default();
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((input, input_line_num)) = pending.take() else {
+            pending = Some((line, line_num));
+            continue;
+        };
+        let expected = line;
+        let url = Url::parse(input)
+            .unwrap_or_else(|e| panic!("failed to parse URL on line {input_line_num}: {e}"));
+        let actual = RenderedLink::new(&url, &opts).to_string();
+        if actual != expected {
+            failures.push(format!(
+                "line {input_line_num}: {input}\n  expected: {expected}\n    actual: {actual}"
+            ));
+        }
+    }
+    assert!(
+        pending.is_none(),
+        "`golden.txt` has a URL without an expected rendering"
+    );
+    assert!(
+        failures.is_empty(),
+        "{} golden case(s) failed:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+/// Applies an `option` or `option = value` directive to `opts`. Options are named after
+/// [`RenderOptions`]' fields; list-valued ones are appended to, and map-valued ones are given
+/// a `key => value` entry.
+fn set_option(opts: &mut RenderOptions, directive: &str) -> Result<(), String> {
+    let (name, arg) = match directive.split_once('=') {
+        Some((name, arg)) => (name.trim(), Some(arg.trim())),
+        None => (directive, None),
+    };
+    let value = || arg.ok_or_else(|| format!("`{name}` needs a value"));
+    let flag = || match arg {
+        None | Some("true") => Ok(true),
+        Some("false") => Ok(false),
+        Some(other) => Err(format!("`{name}` expects `true` or `false`, got `{other}`")),
+    };
+    let entry = || {
+        let value = value()?;
+        value
+            .split_once(" => ")
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .ok_or_else(|| format!("`{name}` expects `key => value`, got `{value}`"))
+    };
+    match name {
+        "disabled_hosts" => opts.disabled_hosts.push(value()?.to_owned()),
+        "github_hosts" => opts.github_hosts.push(value()?.to_owned()),
+        "use_backticks" => opts.use_backticks = flag()?,
+        "path_separator" => {
+            // Quotes allow separators with leading or trailing spaces.
+            let value = value()?;
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            opts.path_separator = value.to_owned().into();
+        }
+        "issue_titles" => {
+            // Each entry is looked up before those given earlier.
+            let (issue, title) = entry()?;
+            let earlier = opts.issue_titles.take();
+            opts.issue_titles = Some(IssueTitleLookup(Box::new(move |key| {
+                if key == issue {
+                    return Some(title.clone());
+                }
+                earlier.as_ref().and_then(|earlier| (earlier.0)(key))
+            })));
+        }
+        "commit_subjects" => {
+            // Keyed like `org/repo@sha`; each entry is looked up before those given earlier.
+            let (commit, subject) = entry()?;
+            let earlier = opts.commit_subjects.take();
+            opts.commit_subjects = Some(CommitSubjectLookup(Box::new(move |repo, sha| {
+                if commit == format!("{repo}@{sha}") {
+                    return Some(subject.clone());
+                }
+                earlier.as_ref().and_then(|earlier| (earlier.0)(repo, sha))
+            })));
+        }
+        "hide_default_branch" => opts.hide_default_branch = Some(value()?.to_owned()),
+        "link_table" => {
+            let (url, label) = entry()?;
+            opts.link_table.insert(url, label);
+        }
+        "annotate_host" => opts.annotate_host = flag()?,
+        "strip_query_on_match" => opts.strip_query_on_match = flag()?,
+        "image_hosts" => opts.image_hosts.push(value()?.to_owned()),
+        "mark_tag_refs" => opts.mark_tag_refs = flag()?,
+        "home_org" => opts.home_org = Some(value()?.to_owned()),
+        "repo_prefix" => {
+            opts.repo_prefix = match value()? {
+                "full" => RepoPrefix::Full,
+                "repo-only" => RepoPrefix::RepoOnly,
+                other => return Err(format!("unknown `repo_prefix` `{other}`")),
+            }
+        }
+        "file_url_display" => {
+            opts.file_url_display = match value()? {
+                "basename" => FileUrlDisplay::Basename,
+                "full-path" => FileUrlDisplay::FullPath,
+                other => return Err(format!("unknown `file_url_display` `{other}`")),
+            }
+        }
+        "aliases" => {
+            let (name, display) = entry()?;
+            opts.aliases.insert(name, display);
+        }
+        "issue_format" => opts.issue_format = value()?.to_owned().into(),
+        "mdn_locale" => opts.mdn_locale = Some(value()?.to_owned()),
+        _ => return Err(format!("unknown option `{name}`")),
+    }
+    Ok(())
+}